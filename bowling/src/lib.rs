@@ -1,10 +1,219 @@
-#[derive(Debug, PartialEq, Eq)]
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum Error {
     NotEnoughPinsLeft,
     GameComplete,
+    NoSuchRoll,
+    PinNotStanding,
+    // No combination of the standing pins is worth the count.
+    ImpossibleCount,
+    // The pins left standing were given for a rack too large for a `PinSet`.
+    RackTooLarge,
+    // A game needs at least one frame to ever be completed.
+    NoFrames,
+}
+
+// A roll of the log that could not be applied, `index` being its position in the log.
+#[derive(Debug, PartialEq, Eq)]
+pub struct RollError {
+    pub index: usize,
+    pub error: Error,
+}
+
+// Errors raised while reading a game from its score-sheet notation. `position` is the
+// index of the offending character in the input.
+#[derive(Debug, PartialEq, Eq)]
+pub enum NotationError {
+    InvalidSymbol { position: usize, symbol: char },
+    // `X` used when the rack is not full.
+    MisplacedStrike { position: usize },
+    // `/` used on a full rack, where there is nothing to spare.
+    MisplacedSpare { position: usize },
+    // A count clearing the rack after the first ball, which must be written as `/`.
+    UnmarkedSpare { position: usize },
+    InvalidRoll { position: usize, error: Error },
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Roll {
+    Pins(u16),
+    // A foul counts as a throw knocking down no pins.
+    Foul,
+    // The pins left standing after the throw, as reported by an automatic scorer.
+    Leave(PinSet),
+}
+
+// A pin number a `PinSet` cannot hold, pins being numbered from 1 to 16.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct InvalidPin(pub u8);
+
+// A set of pins, numbered from 1 as on the pin deck.
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct PinSet(u16);
+
+impl PinSet {
+    pub fn empty() -> Self {
+        Self(0)
+    }
+
+    // All the pins of a rack of `count` pins, `None` for racks of more than 16 pins.
+    pub fn full(count: usize) -> Option<Self> {
+        (count <= 16).then(|| Self(((1u32 << count) - 1) as u16))
+    }
+
+    pub fn contains(&self, pin: u8) -> bool {
+        (1..=16).contains(&pin) && self.0 & (1 << (pin - 1)) != 0
+    }
+
+    pub fn try_insert(&mut self, pin: u8) -> Result<(), InvalidPin> {
+        if !(1..=16).contains(&pin) {
+            return Err(InvalidPin(pin));
+        }
+        self.0 |= 1 << (pin - 1);
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = u8> + '_ {
+        (1..=16).filter(|pin| self.contains(*pin))
+    }
+
+    pub fn is_subset(&self, other: &PinSet) -> bool {
+        self.0 & !other.0 == 0
+    }
+
+    pub fn difference(&self, other: &PinSet) -> PinSet {
+        PinSet(self.0 & !other.0)
+    }
+
+    // Whether these pins, left on a ten-pin deck after the first ball, form a split: the
+    // head pin is down and a fallen pin lies between, or right ahead of, standing pins.
+    pub fn is_split(&self) -> bool {
+        if self.contains(1) || self.len() < 2 {
+            return false;
+        }
+        // Group the standing pins that are close enough for one of them to take out
+        // the other, a split leaving more than one group.
+        let pins: Vec<u8> = self.iter().collect();
+        let mut group = vec![pins[0]];
+        let mut idx = 0;
+        while idx < group.len() {
+            let pin = group[idx];
+            for &other in &pins {
+                if !group.contains(&other) && self.are_connected(pin, other) {
+                    group.push(other);
+                }
+            }
+            idx += 1;
+        }
+        group.len() != pins.len()
+    }
+
+    fn are_connected(&self, pin: u8, other: u8) -> bool {
+        let (Some((row, x)), Some((other_row, other_x))) =
+            (deck_position(pin), deck_position(other))
+        else {
+            return false;
+        };
+        let rows_apart = row.abs_diff(other_row);
+        let columns_apart = x.abs_diff(other_x);
+        match (rows_apart, columns_apart) {
+            // Neighbours on the diagonals of the deck.
+            (1, 1) => true,
+            // A sleeper, hidden right behind another pin.
+            (2, 0) => true,
+            // Neighbours in the same row, as long as the pin ahead of them is standing.
+            (0, 2) => deck_pin(row - 1, (x + other_x) / 2).is_some_and(|pin| self.contains(pin)),
+            _ => false,
+        }
+    }
+
+    pub fn leave(&self) -> Leave {
+        let pins: Vec<u8> = self.iter().collect();
+        match pins.as_slice() {
+            [] => Leave::Clean,
+            [pin] => Leave::SinglePin(*pin),
+            [7, 10] => Leave::SevenTen,
+            [2, 7] | [3, 10] => Leave::BabySplit,
+            [5, 7] | [5, 10] => Leave::DimeStore,
+            [4, 6, 7, 10] => Leave::BigFour,
+            [4, 6, 7, 8, 10] | [4, 6, 7, 9, 10] => Leave::GreekChurch,
+            [2, 4, 5, 8] | [3, 5, 6, 9] => Leave::Bucket,
+            [1, 2, 10] | [1, 2, 4, 10] | [1, 3, 7] | [1, 3, 6, 7] => Leave::Washout,
+            _ if self.is_split() => Leave::Split,
+            _ => Leave::Other,
+        }
+    }
 }
 
-#[derive(PartialEq, Eq, Clone)]
+// Row, counting from the head pin, and column of a pin on the ten-pin deck.
+fn deck_position(pin: u8) -> Option<(u8, i8)> {
+    match pin {
+        1 => Some((0, 0)),
+        2 => Some((1, -1)),
+        3 => Some((1, 1)),
+        4 => Some((2, -2)),
+        5 => Some((2, 0)),
+        6 => Some((2, 2)),
+        7 => Some((3, -3)),
+        8 => Some((3, -1)),
+        9 => Some((3, 1)),
+        10 => Some((3, 3)),
+        _ => None,
+    }
+}
+
+fn deck_pin(row: u8, x: i8) -> Option<u8> {
+    (1..=10).find(|pin| deck_position(*pin) == Some((row, x)))
+}
+
+impl TryFrom<&[u8]> for PinSet {
+    type Error = InvalidPin;
+
+    fn try_from(pins: &[u8]) -> Result<Self, Self::Error> {
+        let mut set = PinSet::empty();
+        for &pin in pins {
+            set.try_insert(pin)?;
+        }
+        Ok(set)
+    }
+}
+
+// Pins joined with dashes, e.g. `7-10`.
+impl fmt::Display for PinSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pins: Vec<_> = self.iter().map(|pin| pin.to_string()).collect();
+        write!(f, "{}", pins.join("-"))
+    }
+}
+
+// Common leaves of the ten-pin deck.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Leave {
+    Clean,
+    SinglePin(u8),
+    SevenTen,
+    BabySplit,
+    DimeStore,
+    BigFour,
+    GreekChurch,
+    Bucket,
+    Washout,
+    Split,
+    Other,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum FrameType {
     Open,
     Spare,
@@ -12,210 +221,1002 @@ pub enum FrameType {
     Last,
 }
 
+impl FrameType {
+    // Type of a frame whose rack was cleared by the given ball, counting from 1. Clearing
+    // the rack with any ball after the second one does not earn a bonus.
+    fn cleared_by(ball: usize) -> Self {
+        match ball {
+            1 => FrameType::Strike,
+            2 => FrameType::Spare,
+            _ => FrameType::Open,
+        }
+    }
+}
+
+// The rules of a bowling variant: the rack of pins, how many balls make up a frame,
+// how many frames make up a game and the bonus earned by clearing the rack.
+pub trait Ruleset {
+    // Value of each pin of the rack.
+    fn pin_values(&self) -> &[u16];
+
+    fn balls_per_frame(&self) -> usize;
+
+    fn frame_count(&self) -> usize;
+
+    // Value of a full rack.
+    fn pins(&self) -> u16 {
+        self.pin_values().iter().sum()
+    }
+
+    // Number of following throws added to the score of a frame of the given type.
+    fn bonus_throws(&self, frame_type: FrameType) -> usize {
+        match frame_type {
+            FrameType::Strike => 2,
+            FrameType::Spare => 1,
+            FrameType::Open | FrameType::Last => 0,
+        }
+    }
+
+    // Balls the last frame may take, as it goes on with the bonus throws once the rack
+    // is cleared.
+    fn balls_in_last_frame(&self) -> usize {
+        (1..=self.balls_per_frame())
+            .map(|ball| ball + self.bonus_throws(FrameType::cleared_by(ball)))
+            .chain(Some(self.balls_per_frame()))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TenPin {
+    frames: usize,
+}
+
+impl Default for TenPin {
+    fn default() -> Self {
+        Self { frames: 10 }
+    }
+}
+
+impl TenPin {
+    pub fn with_frames(frames: usize) -> Result<Self, Error> {
+        match frames {
+            0 => Err(Error::NoFrames),
+            _ => Ok(Self { frames }),
+        }
+    }
+}
+
+impl Ruleset for TenPin {
+    fn pin_values(&self) -> &[u16] {
+        &[1; 10]
+    }
+
+    fn balls_per_frame(&self) -> usize {
+        2
+    }
+
+    fn frame_count(&self) -> usize {
+        self.frames
+    }
+}
+
+// Five pins worth 2, 3, 5, 3 and 2 points, knocked down with up to three balls per frame.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct FivePin {
+    frames: usize,
+}
+
+impl Default for FivePin {
+    fn default() -> Self {
+        Self { frames: 10 }
+    }
+}
+
+impl FivePin {
+    pub fn with_frames(frames: usize) -> Result<Self, Error> {
+        match frames {
+            0 => Err(Error::NoFrames),
+            _ => Ok(Self { frames }),
+        }
+    }
+}
+
+impl Ruleset for FivePin {
+    fn pin_values(&self) -> &[u16] {
+        &[2, 3, 5, 3, 2]
+    }
+
+    fn balls_per_frame(&self) -> usize {
+        3
+    }
+
+    fn frame_count(&self) -> usize {
+        self.frames
+    }
+}
+
+// Ten pins and three balls per frame. The downed wood is not cleared between the balls of
+// a frame, which changes how the pins fall but not how the frame is scored.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Candlepin {
+    frames: usize,
+}
+
+impl Default for Candlepin {
+    fn default() -> Self {
+        Self { frames: 10 }
+    }
+}
+
+impl Candlepin {
+    pub fn with_frames(frames: usize) -> Result<Self, Error> {
+        match frames {
+            0 => Err(Error::NoFrames),
+            _ => Ok(Self { frames }),
+        }
+    }
+}
+
+impl Ruleset for Candlepin {
+    fn pin_values(&self) -> &[u16] {
+        &[1; 10]
+    }
+
+    fn balls_per_frame(&self) -> usize {
+        3
+    }
+
+    fn frame_count(&self) -> usize {
+        self.frames
+    }
+}
+
 #[derive(Clone)]
 pub struct Frame {
-    first_throw: Option<u16>,
-    second_throw: Option<u16>,
-    fill_throw: Option<u16>,
+    throws: Vec<u16>,
+    // Whether the throw at the same position was a foul and therefore counts as zero.
+    fouls: Vec<bool>,
+    // Pins left standing after the throw at the same position, when they are known.
+    leaves: Vec<Option<PinSet>>,
+    // Value of the full rack, the rack being reset once all the pins are down.
+    rack: u16,
     frame_type: FrameType,
-    score: u16,
+    // Cumulative score of the game up to and including this frame. It stays `None`
+    // as long as this frame, or any frame before it, is waiting for bonus throws.
+    score: Option<u16>,
     completed: bool,
 }
 
+impl Default for Frame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Frame {
+    // A frame of ten-pin bowling.
     pub fn new() -> Self {
+        Self::with_rack(10)
+    }
+
+    // A frame played on a rack worth `rack` points.
+    pub fn with_rack(rack: u16) -> Self {
         Self {
-            first_throw: None,
-            second_throw: None,
-            fill_throw: None,
+            throws: Vec::new(),
+            fouls: Vec::new(),
+            leaves: Vec::new(),
+            rack,
             frame_type: FrameType::Open,
-            score: 0,
+            score: None,
             completed: false,
         }
     }
-}
 
-fn is_strike(frame: Option<&Frame>) -> bool {
-    frame
-        .map(|f| f.frame_type == FrameType::Strike)
-        .unwrap_or(false)
-}
+    pub fn throws(&self) -> &[u16] {
+        &self.throws
+    }
+
+    pub fn frame_type(&self) -> FrameType {
+        self.frame_type
+    }
+
+    pub fn score(&self) -> Option<u16> {
+        self.score
+    }
 
-fn get_first_throw(frame: Option<&Frame>) -> u16 {
-    frame.and_then(|f| f.first_throw).unwrap_or(0)
+    pub fn is_complete(&self) -> bool {
+        self.completed
+    }
+
+    pub fn is_resolved(&self) -> bool {
+        self.score.is_some()
+    }
+
+    pub fn is_foul(&self, throw: usize) -> bool {
+        self.fouls.get(throw).copied().unwrap_or(false)
+    }
+
+    pub fn leave(&self, throw: usize) -> Option<PinSet> {
+        self.leaves.get(throw).copied().flatten()
+    }
+
+    // Pins left standing for the next throw and whether they form a fresh rack. The
+    // pins are reset once all of them are down, which can only happen more than once
+    // in the last frame.
+    fn standing(&self) -> (u16, bool) {
+        self.throws
+            .iter()
+            .fold((self.rack, true), |(standing, _), &pins| {
+                if pins == standing {
+                    (self.rack, true)
+                } else {
+                    (standing - pins, false)
+                }
+            })
+    }
+
+    // The last known leave on the standing rack and the throws taken after it. Without
+    // a known leave, the throws are those taken since the rack was set.
+    fn rack_since_leave(&self) -> (Option<PinSet>, &[u16]) {
+        let mut standing = self.rack;
+        let mut leave = None;
+        let mut start = 0;
+        for (idx, &pins) in self.throws.iter().enumerate() {
+            if pins == standing {
+                standing = self.rack;
+                leave = None;
+                start = idx + 1;
+            } else {
+                standing -= pins;
+                if let Some(known) = self.leaves[idx] {
+                    leave = Some(known);
+                    start = idx + 1;
+                }
+            }
+        }
+        (leave, &self.throws[start..])
+    }
+
+    // The ball, counting from 1, that first cleared the rack of this frame.
+    fn cleared_by(&self) -> Option<usize> {
+        let mut knocked = 0;
+        self.throws
+            .iter()
+            .position(|&pins| {
+                knocked += pins;
+                knocked == self.rack
+            })
+            .map(|idx| idx + 1)
+    }
+
+    // Score-sheet symbols of the throws taken in this frame, e.g. `X`, `7` and `/`.
+    fn marks(&self) -> Vec<String> {
+        let mut marks = Vec::new();
+        let mut standing = self.rack;
+        let mut new_rack = true;
+        for (idx, &pins) in self.throws.iter().enumerate() {
+            let mark = if self.fouls[idx] {
+                "F".to_string()
+            } else if pins == standing && new_rack {
+                "X".to_string()
+            } else if pins == standing {
+                "/".to_string()
+            } else if pins == 0 {
+                "-".to_string()
+            } else {
+                pins.to_string()
+            };
+            marks.push(mark);
+            new_rack = pins == standing;
+            standing = if new_rack { self.rack } else { standing - pins };
+        }
+        marks
+    }
+
+    pub fn notation(&self) -> String {
+        self.marks().concat()
+    }
 }
 
-fn get_second_throw(frame: Option<&Frame>) -> u16 {
-    frame.and_then(|f| f.second_throw).unwrap_or(0)
+// The racks left by knocking down pins worth `pins` from `rack`, which holds the number
+// of pins standing for each of `values`.
+fn knock_down(values: &[u16], rack: &[usize], pins: u16) -> Vec<Vec<usize>> {
+    let (Some((&value, values)), Some((&standing, rack))) =
+        (values.split_first(), rack.split_first())
+    else {
+        return if pins == 0 {
+            vec![Vec::new()]
+        } else {
+            Vec::new()
+        };
+    };
+    (0..=standing)
+        .take_while(|&down| down as u16 * value <= pins)
+        .flat_map(|down| {
+            knock_down(values, rack, pins - down as u16 * value)
+                .into_iter()
+                .map(move |mut left| {
+                    left.insert(0, standing - down);
+                    left
+                })
+        })
+        .collect()
 }
 
-pub struct BowlingGame {
+// The game is kept as an append-only log of rolls. The frames are a view derived from
+// the log, rebuilt whenever an earlier roll is undone or corrected.
+#[derive(Clone)]
+pub struct BowlingGame<R = TenPin> {
+    rules: R,
+    rolls: Vec<Roll>,
     frames: Vec<Frame>,
-    score: u16,
     completed: bool,
 }
 
+impl Default for BowlingGame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl BowlingGame {
     pub fn new() -> Self {
+        Self::with_rules(TenPin::default())
+    }
+
+    // Builds a game from a log of rolls, reporting the first roll that is not valid.
+    pub fn replay(rolls: &[Roll]) -> Result<Self, RollError> {
+        Self::replay_with_rules(TenPin::default(), rolls)
+    }
+}
+
+impl<R: Ruleset> BowlingGame<R> {
+    pub fn with_rules(rules: R) -> Self {
         Self {
+            rules,
+            rolls: Vec::new(),
             frames: Vec::new(),
-            score: 0,
             completed: false,
         }
     }
 
+    pub fn replay_with_rules(rules: R, rolls: &[Roll]) -> Result<Self, RollError> {
+        let mut game = Self::with_rules(rules);
+        game.rebuild(rolls)?;
+        Ok(game)
+    }
+
+    pub fn rules(&self) -> &R {
+        &self.rules
+    }
+
     pub fn roll(&mut self, pins: u16) -> Result<(), Error> {
+        self.record(Roll::Pins(pins))
+    }
+
+    pub fn foul(&mut self) -> Result<(), Error> {
+        self.record(Roll::Foul)
+    }
+
+    // Records a throw from the pins left standing after it.
+    pub fn roll_pins(&mut self, standing: PinSet) -> Result<(), Error> {
+        self.record(Roll::Leave(standing))
+    }
+
+    // The pins standing for the next throw, unknown when the previous throw of the rack
+    // was only recorded as a count or when the rack is too large for a `PinSet`.
+    pub fn standing_pins(&self) -> Option<PinSet> {
+        match self.current_frame() {
+            Some(frame) if !frame.standing().1 => frame.leaves.last().copied().flatten(),
+            _ => PinSet::full(self.rules.pin_values().len()),
+        }
+    }
+
+    pub fn rolls(&self) -> &[Roll] {
+        &self.rolls
+    }
+
+    // Takes back the last roll, returning it.
+    pub fn undo(&mut self) -> Option<Roll> {
+        let mut rolls = self.rolls.clone();
+        let roll = rolls.pop()?;
+        self.rebuild(&rolls)
+            .expect("A prefix of a valid log is always valid");
+        Some(roll)
+    }
+
+    // Replaces the roll at `index` in the log. When the correction makes the roll itself,
+    // or any roll after it, invalid the game is left unchanged.
+    pub fn correct(&mut self, index: usize, roll: Roll) -> Result<(), RollError> {
+        let previous = self.rolls.clone();
+        let mut rolls = previous.clone();
+        match rolls.get_mut(index) {
+            Some(entry) => *entry = roll,
+            None => {
+                return Err(RollError {
+                    index,
+                    error: Error::NoSuchRoll,
+                })
+            }
+        }
+        self.rebuild(&rolls).inspect_err(|_| {
+            self.rebuild(&previous)
+                .expect("The log before the correction is valid");
+        })
+    }
+
+    fn rebuild(&mut self, rolls: &[Roll]) -> Result<(), RollError> {
+        self.rolls.clear();
+        self.frames.clear();
+        self.completed = false;
+        for (index, roll) in rolls.iter().enumerate() {
+            self.record(*roll)
+                .map_err(|error| RollError { index, error })?;
+        }
+        Ok(())
+    }
+
+    fn record(&mut self, roll: Roll) -> Result<(), Error> {
+        let (pins, leave) = match roll {
+            Roll::Pins(pins) => (pins, None),
+            Roll::Foul => (0, self.standing_pins()),
+            Roll::Leave(leave) => (self.knocked_down(&leave)?, Some(leave)),
+        };
+        self.apply(pins)?;
+        let frame = self.frames.last_mut().unwrap();
+        frame.fouls.push(roll == Roll::Foul);
+        frame.leaves.push(leave);
+        self.rolls.push(roll);
+        Ok(())
+    }
+
+    // Value of the pins knocked down by a throw leaving `leave` standing.
+    fn knocked_down(&self, leave: &PinSet) -> Result<u16, Error> {
+        let pin_values = self.rules.pin_values();
+        let value =
+            |pins: PinSet| -> u16 { pins.iter().map(|pin| pin_values[pin as usize - 1]).sum() };
+        let rack = PinSet::full(pin_values.len()).ok_or(Error::RackTooLarge)?;
+        if !leave.is_subset(&rack) {
+            return Err(Error::PinNotStanding);
+        }
+        match self.standing_pins() {
+            Some(standing) if leave.is_subset(&standing) => Ok(value(standing.difference(leave))),
+            Some(_) => Err(Error::PinNotStanding),
+            // Without knowing which pins were standing, only their value can be checked.
+            None => self
+                .pins_standing()
+                .checked_sub(value(*leave))
+                .ok_or(Error::PinNotStanding),
+        }
+    }
+
+    fn apply(&mut self, pins: u16) -> Result<(), Error> {
         if self.completed {
             return Err(Error::GameComplete);
         }
-        if pins > 10 {
+        if pins > self.pins_standing() {
             return Err(Error::NotEnoughPinsLeft);
         }
-        let current_frame = match self.frames.last() {
-            Some(frame) if !frame.completed => self.frames.pop().unwrap(),
-            _ => Frame::new(),
-        };
-        let is_last_frame = self.frames.len() == 9;
-        let current_frame = self.update_frame(current_frame, is_last_frame, pins)?;
-        self.frames.push(current_frame);
-        if self.completed {
-            self.compute_frame_scores();
+        if !self.can_knock_down(pins) {
+            return Err(Error::ImpossibleCount);
+        }
+        if self.frames.last().is_none_or(|frame| frame.completed) {
+            let mut frame = Frame::with_rack(self.rules.pins());
+            if self.frames.len() + 1 == self.rules.frame_count() {
+                frame.frame_type = FrameType::Last;
+            }
+            self.frames.push(frame);
+        }
+        let frame = self.frames.last_mut().unwrap();
+        frame.throws.push(pins);
+        match (frame.frame_type, frame.cleared_by()) {
+            // The last frame goes on with the bonus throws once its rack is cleared.
+            (FrameType::Last, Some(ball)) => {
+                let bonus_throws = self.rules.bonus_throws(FrameType::cleared_by(ball));
+                frame.completed = frame.throws.len() == ball + bonus_throws;
+            }
+            (_, Some(ball)) => {
+                frame.frame_type = FrameType::cleared_by(ball);
+                frame.completed = true;
+            }
+            (_, None) => frame.completed = frame.throws.len() == self.rules.balls_per_frame(),
         }
+        self.completed = frame.completed && frame.frame_type == FrameType::Last;
+        self.compute_frame_scores();
         Ok(())
     }
 
-    fn update_frame(
-        &mut self,
-        mut current_frame: Frame,
-        is_last_frame: bool,
-        pins: u16,
-    ) -> Result<Frame, Error> {
-        let mut remaining_pins = 10;
-        if is_last_frame {
-            current_frame.frame_type = FrameType::Last;
-        }
-        match current_frame.first_throw {
-            // The first throw at this frame is already taken
-            Some(first_throw_score) => {
-                let x_in_last_frame = is_last_frame && first_throw_score == 10;
-                if !x_in_last_frame {
-                    remaining_pins -= first_throw_score;
-                }
-                match current_frame.second_throw {
-                    // Second throw at this frame is already taken but still the frame is not
-                    // marked as completed. This can only happen in case of the last frame being
-                    // a strike or a spare.
-                    Some(second_throw_score) => {
-                        // If the pins in the final frame are not cleared out after the first
-                        // two throws (i.e. at the end of two throws, there are still pins left)
-                        // then the input pins should be less than the fill throw in the final frame.
-                        let is_spare = first_throw_score + second_throw_score == 10;
-                        if !is_spare && second_throw_score < 10 {
-                            remaining_pins = 10 - second_throw_score;
-                            if pins > remaining_pins {
-                                return Err(Error::NotEnoughPinsLeft);                            
-                            }
-                        }
-                        current_frame.fill_throw = Some(pins);
-                        // All three throws at the final frame are completed. Mark the frame and
-                        // the game as complete.
-                        current_frame.completed = true;
-                        self.completed = true;
-                    }
-                    // The second throw is not yet taken.
-                    None => {
-                        if pins > remaining_pins {
-                            return Err(Error::NotEnoughPinsLeft);
-                        }
-                        current_frame.second_throw = Some(pins);
-                        // This is not the last frame
-                        if current_frame.frame_type != FrameType::Last {
-                            // If all 10 pins are down as combined result of both throws
-                            // then the frame is a spare.
-                            if pins + first_throw_score == 10 {
-                                current_frame.frame_type = FrameType::Spare;
-                            }
-                            // Second throw on any non-last frame completes it.
-                            current_frame.completed = true;
-                        }
-                        // This is the last frame
-                        else {
-                            let spare_in_last_frame = first_throw_score + pins == 10;
-                            // As long as we don't have two strike or spare in the last frame,
-                            // the second throw in the last frame completes both the frame and the
-                            // game.
-                            if !(x_in_last_frame || spare_in_last_frame || pins == 10) {
-                                current_frame.completed = true;
-                                self.completed = true;
-                            }
-                        }
-                    }
-                }
-            }
-            // This is a completely new frame with no throws recorded yet
-            None => {
-                if pins > remaining_pins {
-                    return Err(Error::NotEnoughPinsLeft);
-                }
-                current_frame.first_throw = Some(pins);
-                // All the pins were down after the first throw. If the current frame
-                // is NOT the last frame, then this constitutes a Strike and completes
-                // the frame.
-                if current_frame.frame_type != FrameType::Last && pins == 10 {
-                    current_frame.frame_type = FrameType::Strike;
-                    current_frame.completed = true;
-                }
-            }
+    // Whether some of the standing pins are worth exactly `pins`. As pins of the same
+    // value are interchangeable, the rack is tracked as the number of pins standing for
+    // each value, following every way the counted throws could have fallen.
+    fn can_knock_down(&self, pins: u16) -> bool {
+        let pin_values = self.rules.pin_values();
+        let mut values = pin_values.to_vec();
+        values.sort_unstable();
+        values.dedup();
+        let by_value = |pins: &mut dyn Iterator<Item = u16>| -> Vec<usize> {
+            let pins: Vec<u16> = pins.collect();
+            values
+                .iter()
+                .map(|value| pins.iter().filter(|pin| *pin == value).count())
+                .collect()
+        };
+        let (leave, throws) = self
+            .current_frame()
+            .map_or((None, &[][..]), Frame::rack_since_leave);
+        let rack = match leave {
+            Some(leave) => by_value(&mut leave.iter().map(|pin| pin_values[pin as usize - 1])),
+            None => by_value(&mut pin_values.iter().copied()),
+        };
+        let mut racks = BTreeSet::from([rack]);
+        for &throw in throws {
+            racks = racks
+                .iter()
+                .flat_map(|rack| knock_down(&values, rack, throw))
+                .collect();
         }
-        Ok(current_frame)
+        racks
+            .iter()
+            .any(|rack| !knock_down(&values, rack, pins).is_empty())
+    }
+
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    // Knocking down every standing pin is never worse than leaving some of them, so the
+    // best possible score is reached by clearing the lane with every remaining throw.
+    pub fn max_possible_score(&self) -> u16
+    where
+        R: Clone,
+    {
+        let mut game = self.clone();
+        while !game.completed {
+            let pins = game.pins_standing();
+            game.roll(pins)
+                .expect("Knocking down all the standing pins is always a valid throw");
+        }
+        game.score().unwrap_or(0)
+    }
+
+    fn current_frame(&self) -> Option<&Frame> {
+        self.frames.last().filter(|frame| !frame.completed)
+    }
+
+    // Value of the pins standing for the next throw.
+    fn pins_standing(&self) -> u16 {
+        self.current_frame()
+            .map_or(self.rules.pins(), |frame| frame.standing().0)
+    }
+
+    // Whether the next throw is the first one on a freshly set rack, i.e. a strike
+    // is possible.
+    fn is_new_rack(&self) -> bool {
+        self.current_frame().is_none_or(|frame| frame.standing().1)
+    }
+
+    // Throws taken after the frame at `idx`, used to settle the bonus of a strike or spare.
+    fn throws_after(&self, idx: usize) -> impl Iterator<Item = u16> + '_ {
+        self.frames[idx + 1..]
+            .iter()
+            .flat_map(|f| f.throws.iter().copied())
+    }
+
+    fn frame_score(&self, idx: usize) -> Option<u16> {
+        let frame = &self.frames[idx];
+        let bonus_throws = self.rules.bonus_throws(frame.frame_type);
+        if bonus_throws == 0 {
+            return frame.completed.then(|| frame.throws.iter().sum());
+        }
+        let bonus: Vec<_> = self.throws_after(idx).take(bonus_throws).collect();
+        (bonus.len() == bonus_throws).then(|| frame.rack + bonus.iter().sum::<u16>())
     }
 
     fn compute_frame_scores(&mut self) {
-        if self.frames.len() != 10 {
-            panic!("Cannot compute score until all 10 frames are completed");
-        }
-        for idx in 0..10 {
-            let second_frame = self.frames.get(idx + 1).cloned();
-            let third_frame = self.frames.get(idx + 2).cloned();
-            let mut frame = self.frames.get_mut(idx).unwrap();
-            match frame.frame_type {
-                FrameType::Last | FrameType::Open => {
-                    frame.score = frame.first_throw.unwrap_or(0)
-                        + frame.second_throw.unwrap_or(0)
-                        + frame.fill_throw.unwrap_or(0);
+        let mut total = Some(0);
+        for idx in 0..self.frames.len() {
+            total = total.zip(self.frame_score(idx)).map(|(t, s)| t + s);
+            self.frames[idx].score = total;
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.completed
+    }
+
+    pub fn score(&self) -> Option<u16> {
+        if !self.completed {
+            None
+        } else {
+            self.frames.last().and_then(|f| f.score)
+        }
+    }
+
+    // Compact score-sheet notation of the game, one group of symbols per frame,
+    // e.g. `X 7/ 9- X X X X X X XXX`.
+    pub fn notation(&self) -> String {
+        self.frames
+            .iter()
+            .map(|f| f.notation())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl FromStr for BowlingGame {
+    type Err = NotationError;
+
+    // Whitespace between the symbols is optional, the frame boundaries follow from
+    // the throws themselves.
+    fn from_str(notation: &str) -> Result<Self, Self::Err> {
+        let mut game = BowlingGame::new();
+        for (position, symbol) in notation.chars().enumerate() {
+            if symbol.is_whitespace() {
+                continue;
+            }
+            let standing = game.pins_standing();
+            let new_rack = game.is_new_rack();
+            let outcome = match symbol {
+                'X' | 'x' if !new_rack => return Err(NotationError::MisplacedStrike { position }),
+                'X' | 'x' => game.roll(standing),
+                '/' if new_rack => return Err(NotationError::MisplacedSpare { position }),
+                '/' => game.roll(standing),
+                '-' => game.roll(0),
+                'F' | 'f' => game.foul(),
+                '0'..='9' => {
+                    let pins = symbol.to_digit(10).unwrap() as u16;
+                    if pins == standing && !new_rack {
+                        return Err(NotationError::UnmarkedSpare { position });
+                    }
+                    game.roll(pins)
                 }
-                FrameType::Spare => {
-                    frame.score = 10 + second_frame.and_then(|f| f.first_throw).unwrap_or(0);
+                _ => return Err(NotationError::InvalidSymbol { position, symbol }),
+            };
+            outcome.map_err(|error| NotationError::InvalidRoll { position, error })?;
+        }
+        Ok(game)
+    }
+}
+
+// Renders the game as a score-sheet grid with the throws of every frame and the
+// running total below them. Totals still waiting for bonus throws are left blank.
+impl<R: Ruleset> fmt::Display for BowlingGame<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let frame_count = self.rules.frame_count();
+        let widths: Vec<usize> = (1..=frame_count)
+            .map(|number| {
+                if number == frame_count {
+                    2 * self.rules.balls_in_last_frame() + 1
+                } else {
+                    2 * self.rules.balls_per_frame() + 1
                 }
-                FrameType::Strike => {
-                    let mut base_score = 10;
-                    if is_strike(second_frame.as_ref()) {
-                        base_score += 10;
-                        if is_strike(third_frame.as_ref()) {
-                            base_score += 10;
-                        }
+            })
+            .collect();
+        let border = widths
+            .iter()
+            .map(|width| "-".repeat(*width))
+            .collect::<Vec<_>>()
+            .join("+");
+        let row = |cells: Vec<String>| {
+            cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:^width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join("|")
+        };
+        let numbers = (1..=frame_count).map(|n| n.to_string()).collect();
+        let marks = (0..frame_count)
+            .map(|idx| {
+                self.frames
+                    .get(idx)
+                    .map(|frame| frame.marks().join(" "))
+                    .unwrap_or_default()
+            })
+            .collect();
+        let totals = (0..frame_count)
+            .map(|idx| {
+                self.frames
+                    .get(idx)
+                    .and_then(|frame| frame.score)
+                    .map(|score| score.to_string())
+                    .unwrap_or_default()
+            })
+            .collect();
+        writeln!(f, "+{}+", border)?;
+        writeln!(f, "|{}|", row(numbers))?;
+        writeln!(f, "+{}+", border)?;
+        writeln!(f, "|{}|", row(marks))?;
+        writeln!(f, "|{}|", row(totals))?;
+        write!(f, "+{}+", border)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SessionError {
+    DuplicateBowler(String),
+    UnknownBowler(String),
+    NotYourTurn { up: Option<String> },
+    Game(Error),
+}
+
+// A lane session where the bowlers take turns, each of them bowling a whole frame
+// before the next one is up.
+pub struct Session<R = TenPin> {
+    bowlers: Vec<String>,
+    games: Vec<BowlingGame<R>>,
+    up: usize,
+}
+
+impl Session {
+    pub fn new(bowlers: &[&str]) -> Result<Self, SessionError> {
+        Self::with_rules(TenPin::default(), bowlers)
+    }
+}
+
+impl<R: Ruleset + Clone> Session<R> {
+    // Bowlers are told apart by name, so no two of them may share one.
+    pub fn with_rules(rules: R, bowlers: &[&str]) -> Result<Self, SessionError> {
+        if let Some(idx) = (1..bowlers.len()).find(|&idx| bowlers[..idx].contains(&bowlers[idx])) {
+            return Err(SessionError::DuplicateBowler(bowlers[idx].to_string()));
+        }
+        Ok(Self {
+            bowlers: bowlers.iter().map(|name| name.to_string()).collect(),
+            games: bowlers
+                .iter()
+                .map(|_| BowlingGame::with_rules(rules.clone()))
+                .collect(),
+            up: 0,
+        })
+    }
+}
+
+impl<R: Ruleset> Session<R> {
+    pub fn bowlers(&self) -> &[String] {
+        &self.bowlers
+    }
+
+    // The bowler whose turn it is, `None` once every game is complete.
+    pub fn up(&self) -> Option<&str> {
+        (!self.is_complete()).then(|| self.bowlers[self.up].as_str())
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.games.iter().all(|game| game.is_complete())
+    }
+
+    pub fn game(&self, bowler: &str) -> Option<&BowlingGame<R>> {
+        self.position(bowler).map(|idx| &self.games[idx])
+    }
+
+    pub fn roll(&mut self, bowler: &str, pins: u16) -> Result<(), SessionError> {
+        self.take_turn(bowler, |game| game.roll(pins))
+    }
+
+    pub fn foul(&mut self, bowler: &str) -> Result<(), SessionError> {
+        self.take_turn(bowler, |game| game.foul())
+    }
+
+    pub fn roll_pins(&mut self, bowler: &str, standing: PinSet) -> Result<(), SessionError> {
+        self.take_turn(bowler, |game| game.roll_pins(standing))
+    }
+
+    fn position(&self, bowler: &str) -> Option<usize> {
+        self.bowlers.iter().position(|name| name == bowler)
+    }
+
+    fn take_turn<F>(&mut self, bowler: &str, throw: F) -> Result<(), SessionError>
+    where
+        F: FnOnce(&mut BowlingGame<R>) -> Result<(), Error>,
+    {
+        let idx = self
+            .position(bowler)
+            .ok_or_else(|| SessionError::UnknownBowler(bowler.to_string()))?;
+        if self.is_complete() {
+            return Err(SessionError::Game(Error::GameComplete));
+        }
+        if idx != self.up {
+            return Err(SessionError::NotYourTurn {
+                up: self.up().map(String::from),
+            });
+        }
+        let game = &mut self.games[idx];
+        throw(game).map_err(SessionError::Game)?;
+        // The turn passes on once the frame is over.
+        if game
+            .frames()
+            .last()
+            .is_some_and(|frame| frame.is_complete())
+        {
+            self.up = (self.up + 1) % self.bowlers.len();
+        }
+        Ok(())
+    }
+}
+
+// Running totals over the completed games of a bowler.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct BowlerStats {
+    games: u32,
+    pins: u32,
+    // Balls thrown at a fresh rack, each of them being a chance for a strike.
+    first_balls: u32,
+    first_ball_pins: u32,
+    strikes: u32,
+    // Balls thrown at the pins left by a first ball, each of them being a chance for a spare.
+    spare_chances: u32,
+    spares: u32,
+    // Games without a single open frame.
+    clean_games: u32,
+    // Spare chances by the pins left standing, for the throws recorded pin by pin.
+    conversions: HashMap<PinSet, Conversion>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct Conversion {
+    pub attempts: u32,
+    pub converted: u32,
+}
+
+impl Conversion {
+    pub fn percentage(&self) -> f64 {
+        100.0 * ratio(self.converted, self.attempts)
+    }
+}
+
+impl BowlerStats {
+    fn record<R: Ruleset>(&mut self, game: &BowlingGame<R>) {
+        self.games += 1;
+        self.pins += u32::from(game.score().unwrap_or(0));
+        let mut clean = true;
+        for frame in game.frames() {
+            let mut standing = frame.rack;
+            let mut ball = 0;
+            for (throw, &pins) in frame.throws().iter().enumerate() {
+                match ball {
+                    0 => {
+                        self.first_balls += 1;
+                        self.first_ball_pins += u32::from(pins);
+                        self.strikes += u32::from(pins == standing);
                     }
-                    if base_score == 10 {
-                        frame.score = base_score
-                            + get_first_throw(second_frame.as_ref())
-                            + get_second_throw(second_frame.as_ref());
-                    } else if base_score == 20 {
-                        frame.score = base_score
-                            + get_first_throw(third_frame.as_ref());
-                    } else {
-                        frame.score = base_score;
+                    1 => {
+                        self.spare_chances += 1;
+                        self.spares += u32::from(pins == standing);
+                        if let Some(leave) = frame.leave(throw - 1) {
+                            let conversion = self.conversions.entry(leave).or_default();
+                            conversion.attempts += 1;
+                            conversion.converted += u32::from(pins == standing);
+                        }
                     }
+                    // Clearing the rack with a later ball does not count as a mark.
+                    _ => {}
                 }
+                if pins == standing {
+                    standing = frame.rack;
+                    ball = 0;
+                } else {
+                    standing -= pins;
+                    ball += 1;
+                }
+            }
+            if frame.cleared_by().is_none_or(|ball| ball > 2) {
+                clean = false;
             }
-            self.score += frame.score;
+        }
+        if clean {
+            self.clean_games += 1;
         }
     }
 
-    pub fn score(&self) -> Option<u16> {
-        if !self.completed {
-            None
-        } else {
-            Some(self.score)
+    pub fn games(&self) -> u32 {
+        self.games
+    }
+
+    pub fn average(&self) -> f64 {
+        ratio(self.pins, self.games)
+    }
+
+    pub fn strike_percentage(&self) -> f64 {
+        100.0 * ratio(self.strikes, self.first_balls)
+    }
+
+    pub fn spare_percentage(&self) -> f64 {
+        100.0 * ratio(self.spares, self.spare_chances)
+    }
+
+    pub fn first_ball_average(&self) -> f64 {
+        ratio(self.first_ball_pins, self.first_balls)
+    }
+
+    pub fn clean_games(&self) -> u32 {
+        self.clean_games
+    }
+
+    pub fn conversion(&self, leave: PinSet) -> Conversion {
+        self.conversions.get(&leave).copied().unwrap_or_default()
+    }
+
+    pub fn conversion_of(&self, kind: Leave) -> Conversion {
+        self.conversion_where(|leave| leave.leave() == kind)
+    }
+
+    pub fn split_conversion(&self) -> Conversion {
+        self.conversion_where(|leave| leave.is_split())
+    }
+
+    // Spare chances summed over all the leaves matching `predicate`.
+    fn conversion_where<P: Fn(&PinSet) -> bool>(&self, predicate: P) -> Conversion {
+        self.conversions
+            .iter()
+            .filter(|(leave, _)| predicate(leave))
+            .fold(Conversion::default(), |total, (_, conversion)| Conversion {
+                attempts: total.attempts + conversion.attempts,
+                converted: total.converted + conversion.converted,
+            })
+    }
+
+    // Handicap given as `percentage` of the difference between `base` and the bowler's
+    // average, both the average and the handicap being rounded down.
+    pub fn handicap(&self, base: u32, percentage: u32) -> u32 {
+        let average = self.pins.checked_div(self.games).unwrap_or(0);
+        base.saturating_sub(average) * percentage / 100
+    }
+}
+
+fn ratio(numerator: u32, denominator: u32) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        f64::from(numerator) / f64::from(denominator)
+    }
+}
+
+// Only completed games and sessions count towards the statistics of a league.
+#[derive(Debug, PartialEq, Eq)]
+pub enum LeagueError {
+    GameInProgress,
+    SessionInProgress,
+}
+
+// Statistics of the bowlers of a league, aggregated over their completed games.
+#[derive(Debug, Default)]
+pub struct League {
+    stats: HashMap<String, BowlerStats>,
+}
+
+impl League {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record<R: Ruleset>(
+        &mut self,
+        bowler: &str,
+        game: &BowlingGame<R>,
+    ) -> Result<(), LeagueError> {
+        if !game.is_complete() {
+            return Err(LeagueError::GameInProgress);
         }
+        self.stats
+            .entry(bowler.to_string())
+            .or_default()
+            .record(game);
+        Ok(())
+    }
+
+    pub fn record_session<R: Ruleset>(&mut self, session: &Session<R>) -> Result<(), LeagueError> {
+        if !session.is_complete() {
+            return Err(LeagueError::SessionInProgress);
+        }
+        for (bowler, game) in session.bowlers.iter().zip(&session.games) {
+            self.record(bowler, game)?;
+        }
+        Ok(())
+    }
+
+    pub fn stats(&self, bowler: &str) -> Option<&BowlerStats> {
+        self.stats.get(bowler)
     }
 }
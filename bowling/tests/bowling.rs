@@ -416,3 +416,803 @@ fn last_two_strikes_followed_by_only_last_bonus_with_non_strike_points() {
 
     assert_eq!(game.score(), Some(31));
 }
+
+#[test]
+fn frames_are_scored_before_the_game_is_complete() {
+    let mut game = BowlingGame::new();
+
+    let _ = game.roll(3);
+    let _ = game.roll(4);
+    let _ = game.roll(2);
+    let _ = game.roll(5);
+
+    let frames = game.frames();
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].throws(), vec![3, 4]);
+    assert_eq!(frames[0].frame_type(), FrameType::Open);
+    assert_eq!(frames[0].score(), Some(7));
+    assert_eq!(frames[1].score(), Some(14));
+    assert_eq!(game.score(), None);
+}
+
+#[test]
+fn a_frame_in_progress_is_not_scored() {
+    let mut game = BowlingGame::new();
+
+    let _ = game.roll(3);
+
+    let frames = game.frames();
+    assert_eq!(frames.len(), 1);
+    assert!(!frames[0].is_complete());
+    assert_eq!(frames[0].score(), None);
+}
+
+#[test]
+fn strikes_and_spares_are_unresolved_until_their_bonus_is_rolled() {
+    let mut game = BowlingGame::new();
+
+    let _ = game.roll(10);
+    let _ = game.roll(6);
+    let _ = game.roll(4);
+
+    let frames = game.frames();
+    assert_eq!(frames[0].frame_type(), FrameType::Strike);
+    assert_eq!(frames[0].score(), Some(20));
+    assert_eq!(frames[1].frame_type(), FrameType::Spare);
+    assert!(!frames[1].is_resolved());
+
+    let _ = game.roll(5);
+
+    assert_eq!(game.frames()[1].score(), Some(35));
+}
+
+#[test]
+fn frames_after_an_unresolved_frame_have_no_cumulative_score() {
+    let mut game = BowlingGame::new();
+
+    let _ = game.roll(10);
+    let _ = game.roll(3);
+    let _ = game.roll(4);
+
+    assert_eq!(game.frames()[0].score(), Some(17));
+    assert_eq!(game.frames()[1].score(), Some(24));
+
+    let mut game = BowlingGame::new();
+
+    let _ = game.roll(10);
+    let _ = game.roll(10);
+    let _ = game.roll(3);
+    let _ = game.roll(4);
+
+    assert_eq!(game.frames()[0].score(), Some(23));
+    assert_eq!(game.frames()[2].score(), Some(47));
+}
+
+#[test]
+fn a_rejected_roll_leaves_the_frame_untouched() {
+    let mut game = BowlingGame::new();
+
+    let _ = game.roll(6);
+    assert_eq!(game.roll(5), Err(Error::NotEnoughPinsLeft));
+    assert!(game.roll(4).is_ok());
+
+    assert_eq!(game.frames().len(), 1);
+    assert_eq!(game.frames()[0].throws(), vec![6, 4]);
+}
+
+#[test]
+fn max_possible_score_of_a_new_game_is_a_perfect_game() {
+    let game = BowlingGame::new();
+
+    assert_eq!(game.max_possible_score(), 300);
+}
+
+#[test]
+fn max_possible_score_accounts_for_the_rolls_already_taken() {
+    let mut game = BowlingGame::new();
+
+    let _ = game.roll(3);
+    assert_eq!(game.max_possible_score(), 290);
+
+    let _ = game.roll(4);
+    assert_eq!(game.max_possible_score(), 277);
+}
+
+#[test]
+fn max_possible_score_of_a_completed_game_is_its_score() {
+    let mut game = BowlingGame::new();
+
+    for _ in 0..20 {
+        let _ = game.roll(2);
+    }
+
+    assert_eq!(game.max_possible_score(), 40);
+}
+
+#[test]
+fn a_game_is_rendered_in_score_sheet_notation() {
+    let mut game = BowlingGame::new();
+
+    for pins in [10, 7, 3, 9, 0] {
+        let _ = game.roll(pins);
+    }
+    let _ = game.foul();
+    let _ = game.roll(8);
+
+    assert_eq!(game.notation(), "X 7/ 9- F8");
+    assert!(game.frames()[3].is_foul(0));
+}
+
+#[test]
+fn a_game_is_parsed_from_score_sheet_notation() {
+    let game: BowlingGame = "X 7/ 9- X X X X X X XXX".parse().unwrap();
+
+    assert_eq!(game.score(), Some(258));
+    assert_eq!(game.notation(), "X 7/ 9- X X X X X X XXX");
+}
+
+#[test]
+fn a_foul_counts_as_zero_pins() {
+    let game: BowlingGame = "F/ F- ".parse().unwrap();
+
+    assert_eq!(game.frames()[0].frame_type(), FrameType::Spare);
+    assert_eq!(game.frames()[0].score(), Some(10));
+    assert_eq!(game.frames()[1].score(), Some(10));
+    assert_eq!(game.notation(), "F/ F-");
+}
+
+#[test]
+fn the_score_sheet_shows_running_totals() {
+    let game: BowlingGame = "X 7/ 9- X".parse().unwrap();
+
+    let expected = "\
++-----+-----+-----+-----+-----+-----+-----+-----+-----+-------+
+|  1  |  2  |  3  |  4  |  5  |  6  |  7  |  8  |  9  |  10   |
++-----+-----+-----+-----+-----+-----+-----+-----+-----+-------+
+|  X  | 7 / | 9 - |  X  |     |     |     |     |     |       |
+| 20  | 39  | 48  |     |     |     |     |     |     |       |
++-----+-----+-----+-----+-----+-----+-----+-----+-----+-------+";
+    assert_eq!(game.to_string(), expected);
+}
+
+#[test]
+fn parsing_rejects_impossible_sequences() {
+    assert_eq!(
+        "X 7 X".parse::<BowlingGame>().err(),
+        Some(NotationError::MisplacedStrike { position: 4 })
+    );
+    assert_eq!(
+        "X /".parse::<BowlingGame>().err(),
+        Some(NotationError::MisplacedSpare { position: 2 })
+    );
+    assert_eq!(
+        "X 55".parse::<BowlingGame>().err(),
+        Some(NotationError::UnmarkedSpare { position: 3 })
+    );
+    assert_eq!(
+        "X 7Z".parse::<BowlingGame>().err(),
+        Some(NotationError::InvalidSymbol {
+            position: 3,
+            symbol: 'Z'
+        })
+    );
+    assert_eq!(
+        "X 78".parse::<BowlingGame>().err(),
+        Some(NotationError::InvalidRoll {
+            position: 3,
+            error: Error::NotEnoughPinsLeft
+        })
+    );
+    assert_eq!(
+        "X X X X X X X X X XXX X".parse::<BowlingGame>().err(),
+        Some(NotationError::InvalidRoll {
+            position: 22,
+            error: Error::GameComplete
+        })
+    );
+}
+
+#[test]
+fn the_rolls_are_kept_as_a_log() {
+    let mut game = BowlingGame::new();
+
+    let _ = game.roll(10);
+    let _ = game.foul();
+    let _ = game.roll(11);
+    let _ = game.roll(7);
+
+    assert_eq!(game.rolls(), &[Roll::Pins(10), Roll::Foul, Roll::Pins(7)]);
+}
+
+#[test]
+fn undo_takes_back_the_last_roll() {
+    let mut game = BowlingGame::new();
+
+    let _ = game.roll(10);
+    let _ = game.roll(3);
+
+    assert_eq!(game.undo(), Some(Roll::Pins(3)));
+    assert_eq!(game.rolls(), &[Roll::Pins(10)]);
+    assert_eq!(game.frames().len(), 1);
+    assert_eq!(game.frames()[0].score(), None);
+    assert_eq!(game.undo(), Some(Roll::Pins(10)));
+    assert_eq!(game.undo(), None);
+}
+
+#[test]
+fn undo_reopens_a_completed_game() {
+    let mut game = BowlingGame::new();
+
+    for _ in 0..20 {
+        let _ = game.roll(1);
+    }
+    assert_eq!(game.score(), Some(20));
+
+    let _ = game.undo();
+    assert_eq!(game.score(), None);
+    assert!(game.roll(8).is_ok());
+    assert_eq!(game.score(), Some(27));
+}
+
+#[test]
+fn a_game_is_replayed_from_its_rolls() {
+    let game = BowlingGame::replay(&[Roll::Pins(7), Roll::Pins(3), Roll::Foul]).unwrap();
+
+    assert_eq!(game.notation(), "7/ F");
+    assert_eq!(game.frames()[0].score(), Some(10));
+}
+
+#[test]
+fn replay_reports_the_first_invalid_roll() {
+    assert_eq!(
+        BowlingGame::replay(&[Roll::Pins(7), Roll::Pins(4)]).err(),
+        Some(RollError {
+            index: 1,
+            error: Error::NotEnoughPinsLeft
+        })
+    );
+}
+
+#[test]
+fn correcting_an_earlier_roll_recomputes_the_frames() {
+    let mut game = BowlingGame::new();
+
+    for pins in [3, 4, 10, 2, 2] {
+        let _ = game.roll(pins);
+    }
+    assert_eq!(game.frames()[2].score(), Some(25));
+
+    assert!(game.correct(1, Roll::Pins(7)).is_ok());
+
+    assert_eq!(game.notation(), "3/ X 22");
+    assert_eq!(game.frames()[2].score(), Some(38));
+}
+
+#[test]
+fn an_invalid_correction_leaves_the_game_unchanged() {
+    let mut game = BowlingGame::new();
+
+    for pins in [3, 4, 5] {
+        let _ = game.roll(pins);
+    }
+
+    assert_eq!(
+        game.correct(0, Roll::Pins(8)),
+        Err(RollError {
+            index: 1,
+            error: Error::NotEnoughPinsLeft
+        })
+    );
+    assert_eq!(
+        game.correct(3, Roll::Pins(1)),
+        Err(RollError {
+            index: 3,
+            error: Error::NoSuchRoll
+        })
+    );
+    assert_eq!(game.notation(), "34 5");
+}
+
+#[test]
+fn a_correction_can_turn_a_roll_into_a_foul() {
+    let mut game = BowlingGame::new();
+
+    let _ = game.roll(3);
+    let _ = game.roll(4);
+
+    assert!(game.correct(0, Roll::Foul).is_ok());
+    assert_eq!(game.notation(), "F4");
+    assert_eq!(game.frames()[0].score(), Some(4));
+}
+
+#[test]
+fn ten_pin_is_the_default_ruleset() {
+    let game = BowlingGame::new();
+
+    assert_eq!(game.rules(), &TenPin::default());
+    assert_eq!(game.rules().pins(), 10);
+    assert_eq!(game.rules().frame_count(), 10);
+}
+
+#[test]
+fn a_ten_pin_game_can_have_a_custom_number_of_frames() {
+    let mut game = BowlingGame::with_rules(TenPin::with_frames(3).unwrap());
+
+    for _ in 0..5 {
+        let _ = game.roll(10);
+    }
+
+    assert_eq!(game.score(), Some(90));
+    assert_eq!(game.roll(10), Err(Error::GameComplete));
+}
+
+#[test]
+fn five_pin_frames_take_up_to_three_balls() {
+    let mut game = BowlingGame::with_rules(FivePin::default());
+
+    let _ = game.roll(5);
+    let _ = game.roll(3);
+    assert!(!game.frames()[0].is_complete());
+    let _ = game.roll(2);
+
+    assert_eq!(game.frames()[0].frame_type(), FrameType::Open);
+    assert_eq!(game.frames()[0].score(), Some(10));
+    assert_eq!(game.roll(16), Err(Error::NotEnoughPinsLeft));
+}
+
+#[test]
+fn clearing_the_rack_with_the_third_ball_earns_no_bonus() {
+    let mut game = BowlingGame::with_rules(FivePin::default());
+
+    let _ = game.roll(5);
+    let _ = game.roll(5);
+    let _ = game.roll(5);
+
+    assert_eq!(game.frames()[0].frame_type(), FrameType::Open);
+    assert_eq!(game.frames()[0].score(), Some(15));
+}
+
+#[test]
+fn a_perfect_five_pin_game_scores_450() {
+    let mut game = BowlingGame::with_rules(FivePin::default());
+
+    for _ in 0..12 {
+        let _ = game.roll(15);
+    }
+
+    assert_eq!(game.score(), Some(450));
+    assert_eq!(
+        BowlingGame::with_rules(FivePin::default()).max_possible_score(),
+        450
+    );
+}
+
+#[test]
+fn five_pin_spares_earn_the_next_ball_as_bonus() {
+    let mut game = BowlingGame::with_rules(FivePin::default());
+
+    let _ = game.roll(10);
+    let _ = game.roll(5);
+    let _ = game.roll(7);
+
+    assert_eq!(game.frames()[0].frame_type(), FrameType::Spare);
+    assert_eq!(game.frames()[0].score(), Some(22));
+}
+
+#[test]
+fn five_pin_counts_must_be_worth_some_of_the_standing_pins() {
+    let mut game = BowlingGame::with_rules(FivePin::default());
+
+    assert_eq!(game.roll(1), Err(Error::ImpossibleCount));
+    assert_eq!(game.roll(14), Err(Error::ImpossibleCount));
+    assert_eq!(game.roll(13), Ok(()));
+    // Only a two is left standing.
+    assert_eq!(game.roll(1), Err(Error::ImpossibleCount));
+    assert_eq!(game.roll(2), Ok(()));
+}
+
+#[test]
+fn five_pin_counts_follow_every_way_the_earlier_counts_could_have_fallen() {
+    let mut game = BowlingGame::with_rules(FivePin::default());
+
+    // Either the head pin or a two and a three, leaving 2-3-3-2 or 2-3-5.
+    let _ = game.roll(5);
+    assert_eq!(game.roll(8), Ok(()));
+    let mut game = BowlingGame::with_rules(FivePin::default());
+    let _ = game.roll(5);
+    // Neither leave has pins worth 9 but not the rest.
+    assert_eq!(game.roll(9), Err(Error::ImpossibleCount));
+}
+
+#[test]
+fn five_pin_counts_are_checked_against_a_known_leave() {
+    let mut game = BowlingGame::with_rules(FivePin::default());
+
+    let _ = game.roll_pins(PinSet::try_from(&[1, 5][..]).unwrap());
+    assert_eq!(game.roll(3), Err(Error::ImpossibleCount));
+    assert_eq!(game.roll(2), Ok(()));
+}
+
+#[test]
+fn a_game_needs_at_least_one_frame() {
+    assert_eq!(TenPin::with_frames(0), Err(Error::NoFrames));
+    assert_eq!(FivePin::with_frames(0), Err(Error::NoFrames));
+    assert_eq!(Candlepin::with_frames(0), Err(Error::NoFrames));
+    assert!(Candlepin::with_frames(1).is_ok());
+}
+
+#[test]
+fn the_last_candlepin_frame_takes_three_balls_even_without_a_mark() {
+    let mut game = BowlingGame::with_rules(Candlepin::default());
+
+    for _ in 0..27 {
+        let _ = game.roll(2);
+    }
+    assert_eq!(game.score(), None);
+    let _ = game.roll(2);
+    let _ = game.roll(2);
+    let _ = game.roll(2);
+
+    assert_eq!(game.score(), Some(60));
+    assert_eq!(game.notation().split(' ').next_back(), Some("222"));
+}
+
+#[test]
+fn a_perfect_candlepin_game_scores_300() {
+    let mut game = BowlingGame::with_rules(Candlepin::default());
+
+    for _ in 0..12 {
+        let _ = game.roll(10);
+    }
+
+    assert_eq!(game.score(), Some(300));
+}
+
+#[derive(Clone)]
+struct NoBonus;
+
+impl Ruleset for NoBonus {
+    fn pin_values(&self) -> &[u16] {
+        &[1; 6]
+    }
+
+    fn balls_per_frame(&self) -> usize {
+        2
+    }
+
+    fn frame_count(&self) -> usize {
+        2
+    }
+
+    fn bonus_throws(&self, _: FrameType) -> usize {
+        0
+    }
+}
+
+#[test]
+fn a_custom_ruleset_controls_the_bonus() {
+    let mut game = BowlingGame::with_rules(NoBonus);
+
+    let _ = game.roll(6);
+    let _ = game.roll(2);
+    let _ = game.roll(4);
+
+    assert_eq!(game.frames()[0].score(), Some(6));
+    assert_eq!(game.score(), Some(12));
+    assert_eq!(game.roll(1), Err(Error::GameComplete));
+}
+
+#[test]
+fn bowlers_take_turns_frame_by_frame() {
+    let mut session = Session::new(&["Ann", "Bob"]).unwrap();
+
+    assert_eq!(session.up(), Some("Ann"));
+    assert!(session.roll("Ann", 3).is_ok());
+    assert_eq!(session.up(), Some("Ann"));
+    assert!(session.roll("Ann", 4).is_ok());
+    assert_eq!(session.up(), Some("Bob"));
+    assert!(session.roll("Bob", 10).is_ok());
+    assert_eq!(session.up(), Some("Ann"));
+}
+
+#[test]
+fn a_bowler_cannot_roll_out_of_turn() {
+    let mut session = Session::new(&["Ann", "Bob"]).unwrap();
+
+    assert_eq!(
+        session.roll("Bob", 3),
+        Err(SessionError::NotYourTurn {
+            up: Some("Ann".to_string())
+        })
+    );
+    assert_eq!(
+        session.roll("Cid", 3),
+        Err(SessionError::UnknownBowler("Cid".to_string()))
+    );
+    assert!(session.game("Bob").unwrap().frames().is_empty());
+}
+
+#[test]
+fn bowlers_of_a_session_must_have_distinct_names() {
+    assert_eq!(
+        Session::new(&["Ann", "Bob", "Ann"]).err(),
+        Some(SessionError::DuplicateBowler("Ann".to_string()))
+    );
+}
+
+#[test]
+fn an_invalid_roll_does_not_pass_the_turn() {
+    let mut session = Session::new(&["Ann", "Bob"]).unwrap();
+
+    let _ = session.roll("Ann", 6);
+
+    assert_eq!(
+        session.roll("Ann", 6),
+        Err(SessionError::Game(Error::NotEnoughPinsLeft))
+    );
+    assert_eq!(session.up(), Some("Ann"));
+}
+
+#[test]
+fn a_session_is_complete_once_every_game_is() {
+    let mut session = Session::new(&["Ann", "Bob"]).unwrap();
+
+    for _ in 0..9 {
+        let _ = session.roll("Ann", 10);
+        let _ = session.roll("Bob", 1);
+        let _ = session.roll("Bob", 1);
+    }
+    for _ in 0..3 {
+        let _ = session.roll("Ann", 10);
+    }
+    assert_eq!(session.up(), Some("Bob"));
+    let _ = session.roll("Bob", 1);
+    let _ = session.roll("Bob", 1);
+
+    assert!(session.is_complete());
+    assert_eq!(session.up(), None);
+    assert_eq!(session.game("Ann").unwrap().score(), Some(300));
+    assert_eq!(session.game("Bob").unwrap().score(), Some(20));
+    assert_eq!(
+        session.roll("Ann", 1),
+        Err(SessionError::Game(Error::GameComplete))
+    );
+}
+
+#[test]
+fn only_completed_games_count_towards_the_league_statistics() {
+    let mut league = League::new();
+    let mut game = BowlingGame::new();
+
+    let _ = game.roll(10);
+
+    assert_eq!(
+        league.record("Ann", &game),
+        Err(LeagueError::GameInProgress)
+    );
+    assert!(league.stats("Ann").is_none());
+}
+
+#[test]
+fn league_statistics_are_aggregated_per_bowler() {
+    let mut league = League::new();
+    let spares: BowlingGame = "9/ 9/ 9/ 9/ 9/ 9/ 9/ 9/ 9/ 9/9".parse().unwrap();
+    let opens: BowlingGame = "44 44 44 44 44 44 44 44 44 44".parse().unwrap();
+
+    assert!(league.record("Ann", &spares).is_ok());
+    assert!(league.record("Ann", &opens).is_ok());
+
+    let stats = league.stats("Ann").unwrap();
+    assert_eq!(stats.games(), 2);
+    assert_eq!(stats.average(), 135.0);
+    assert_eq!(stats.strike_percentage(), 0.0);
+    assert_eq!(stats.spare_percentage(), 50.0);
+    assert_eq!(stats.first_ball_average(), 139.0 / 21.0);
+    assert_eq!(stats.clean_games(), 1);
+}
+
+#[test]
+fn a_perfect_game_is_all_strikes_and_clean() {
+    let mut league = League::new();
+    let game: BowlingGame = "X X X X X X X X X XXX".parse().unwrap();
+
+    let _ = league.record("Ann", &game);
+
+    let stats = league.stats("Ann").unwrap();
+    assert_eq!(stats.strike_percentage(), 100.0);
+    assert_eq!(stats.first_ball_average(), 10.0);
+    assert_eq!(stats.clean_games(), 1);
+    assert_eq!(stats.handicap(200, 90), 0);
+}
+
+#[test]
+fn handicap_is_a_percentage_of_the_difference_to_the_base() {
+    let mut league = League::new();
+    let spares: BowlingGame = "9/ 9/ 9/ 9/ 9/ 9/ 9/ 9/ 9/ 9/9".parse().unwrap();
+    let opens: BowlingGame = "44 44 44 44 44 44 44 44 44 44".parse().unwrap();
+
+    let _ = league.record("Ann", &spares);
+    let _ = league.record("Ann", &opens);
+
+    assert_eq!(league.stats("Ann").unwrap().handicap(200, 90), 58);
+    assert_eq!(league.stats("Ann").unwrap().handicap(220, 80), 68);
+}
+
+#[test]
+fn a_completed_session_is_recorded_for_every_bowler() {
+    let mut session = Session::new(&["Ann", "Bob"]).unwrap();
+    let mut league = League::new();
+
+    assert_eq!(
+        league.record_session(&session),
+        Err(LeagueError::SessionInProgress)
+    );
+    for _ in 0..10 {
+        let _ = session.roll("Ann", 4);
+        let _ = session.roll("Ann", 4);
+        let _ = session.roll("Bob", 5);
+        let _ = session.roll("Bob", 4);
+    }
+
+    assert!(league.record_session(&session).is_ok());
+    assert_eq!(league.stats("Ann").unwrap().average(), 80.0);
+    assert_eq!(league.stats("Bob").unwrap().average(), 90.0);
+}
+
+fn pins(pins: &[u8]) -> PinSet {
+    PinSet::try_from(pins).unwrap()
+}
+
+#[test]
+fn a_throw_is_recorded_from_the_pins_left_standing() {
+    let mut game = BowlingGame::new();
+
+    assert!(game.roll_pins(pins(&[7, 10])).is_ok());
+    assert_eq!(game.standing_pins(), Some(pins(&[7, 10])));
+    assert!(game.roll_pins(pins(&[10])).is_ok());
+
+    assert_eq!(game.notation(), "81");
+    assert_eq!(game.frames()[0].leave(0), Some(pins(&[7, 10])));
+    assert_eq!(game.standing_pins(), PinSet::full(10));
+}
+
+#[test]
+fn a_pin_that_was_already_down_cannot_be_standing() {
+    let mut game = BowlingGame::new();
+
+    let _ = game.roll_pins(pins(&[7, 10]));
+
+    assert_eq!(game.roll_pins(pins(&[9])), Err(Error::PinNotStanding));
+    assert_eq!(game.roll_pins(pins(&[11])), Err(Error::PinNotStanding));
+    assert_eq!(game.rolls(), &[Roll::Leave(pins(&[7, 10]))]);
+}
+
+#[test]
+fn pin_level_and_count_based_throws_can_be_mixed() {
+    let mut game = BowlingGame::new();
+
+    let _ = game.roll(6);
+    assert_eq!(game.standing_pins(), None);
+    assert_eq!(
+        game.roll_pins(pins(&[1, 2, 3, 4, 5])),
+        Err(Error::PinNotStanding)
+    );
+    assert!(game.roll_pins(pins(&[1])).is_ok());
+    assert!(game.roll_pins(PinSet::empty()).is_ok());
+
+    assert_eq!(game.notation(), "63 X");
+}
+
+#[test]
+fn pin_sets_only_hold_pins_1_to_16() {
+    let mut set = PinSet::empty();
+
+    assert_eq!(set.try_insert(16), Ok(()));
+    assert_eq!(set.try_insert(0), Err(InvalidPin(0)));
+    assert_eq!(PinSet::try_from(&[3, 17][..]), Err(InvalidPin(17)));
+    assert_eq!(PinSet::full(16).map(|rack| rack.len()), Some(16));
+    assert_eq!(PinSet::full(17), None);
+}
+
+struct TwentyPin;
+
+impl Ruleset for TwentyPin {
+    fn pin_values(&self) -> &[u16] {
+        &[1; 20]
+    }
+
+    fn balls_per_frame(&self) -> usize {
+        2
+    }
+
+    fn frame_count(&self) -> usize {
+        10
+    }
+}
+
+#[test]
+fn the_pins_of_a_rack_larger_than_a_pin_set_are_unknown() {
+    let mut game = BowlingGame::with_rules(TwentyPin);
+
+    assert_eq!(game.standing_pins(), None);
+    assert_eq!(game.foul(), Ok(()));
+    assert_eq!(game.roll(20), Ok(()));
+    assert_eq!(game.roll(12), Ok(()));
+    assert_eq!(game.foul(), Ok(()));
+    assert_eq!(game.standing_pins(), None);
+    assert_eq!(game.roll_pins(pins(&[1])), Err(Error::RackTooLarge));
+
+    assert_eq!(game.frames()[1].throws(), &[12, 0]);
+}
+
+#[test]
+fn five_pin_throws_are_valued_by_the_pins_knocked_down() {
+    let mut game = BowlingGame::with_rules(FivePin::default());
+
+    let _ = game.roll_pins(pins(&[1, 5]));
+    let _ = game.roll_pins(pins(&[1]));
+    let _ = game.roll_pins(PinSet::empty());
+
+    assert_eq!(game.frames()[0].throws(), &[11, 2, 2]);
+}
+
+#[test]
+fn splits_are_detected() {
+    for split in [
+        &[7, 10][..],
+        &[2, 7],
+        &[3, 10],
+        &[4, 6],
+        &[5, 6],
+        &[4, 6, 7, 10],
+    ] {
+        assert!(pins(split).is_split(), "{} should be a split", pins(split));
+    }
+    for leave in [
+        &[1, 2, 10][..],
+        &[2, 8],
+        &[2, 4, 5, 8],
+        &[6, 10],
+        &[10],
+        &[],
+    ] {
+        assert!(!pins(leave).is_split(), "{} is not a split", pins(leave));
+    }
+}
+
+#[test]
+fn common_leaves_are_named() {
+    assert_eq!(pins(&[7, 10]).leave(), Leave::SevenTen);
+    assert_eq!(pins(&[3, 10]).leave(), Leave::BabySplit);
+    assert_eq!(pins(&[2, 4, 5, 8]).leave(), Leave::Bucket);
+    assert_eq!(pins(&[1, 2, 4, 10]).leave(), Leave::Washout);
+    assert_eq!(pins(&[4, 6, 7, 9, 10]).leave(), Leave::GreekChurch);
+    assert_eq!(pins(&[10]).leave(), Leave::SinglePin(10));
+    assert_eq!(pins(&[8, 10]).leave(), Leave::Split);
+    assert_eq!(pins(&[1, 2]).leave(), Leave::Other);
+    assert_eq!(pins(&[7, 10]).to_string(), "7-10");
+}
+
+#[test]
+fn spare_conversions_are_tracked_by_leave() {
+    let mut game = BowlingGame::new();
+    for _ in 0..5 {
+        let _ = game.roll_pins(pins(&[7, 10]));
+        let _ = game.roll_pins(pins(&[7]));
+        let _ = game.roll_pins(pins(&[10]));
+        let _ = game.roll_pins(PinSet::empty());
+    }
+    let _ = game.roll(0);
+    let mut league = League::new();
+
+    let _ = league.record("Ann", &game);
+
+    let stats = league.stats("Ann").unwrap();
+    let seven_ten = stats.conversion(pins(&[7, 10]));
+    assert_eq!(seven_ten.attempts, 5);
+    assert_eq!(seven_ten.converted, 0);
+    assert_eq!(stats.conversion(pins(&[10])).percentage(), 100.0);
+    assert_eq!(stats.conversion_of(Leave::SinglePin(10)).attempts, 5);
+    assert_eq!(stats.split_conversion().attempts, 5);
+    assert_eq!(stats.spare_percentage(), 50.0);
+}
@@ -1,43 +1,4326 @@
 pub mod graph {
-    use std::collections::HashMap;
     use graph_items::edge::Edge;
     use graph_items::node::Node;
+    use graph_items::subgraph::Subgraph;
+    use std::collections::HashMap;
+    use std::fmt;
+    use std::ops::{Deref, DerefMut};
+    use std::str::FromStr;
+    use std::sync::OnceLock;
+
+    #[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+    #[cfg_attr(
+        feature = "serde",
+        derive(serde::Serialize, serde::Deserialize),
+        serde(rename_all = "lowercase")
+    )]
+    pub enum GraphKind {
+        #[default]
+        Undirected,
+        Directed,
+    }
+
+    impl GraphKind {
+        fn keyword(&self) -> &'static str {
+            match self {
+                GraphKind::Undirected => "graph",
+                GraphKind::Directed => "digraph",
+            }
+        }
+
+        fn edge_op(&self) -> &'static str {
+            match self {
+                GraphKind::Undirected => "--",
+                GraphKind::Directed => "->",
+            }
+        }
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone)]
+    pub enum GraphError {
+        DuplicateNode(String),
+        UnknownNode(String),
+    }
+
+    // The nodes of a graph in the order they were added, used as a `Vec<Node>`. An index
+    // from their names to their position saves lookups from scanning them. It is built
+    // on the first lookup and dropped whenever the nodes are borrowed mutably.
+    #[derive(Clone, Default)]
+    pub struct NodeList {
+        nodes: Vec<Node>,
+        index: OnceLock<HashMap<String, usize>>,
+    }
+
+    impl NodeList {
+        // Position of the node with the given name, the first one if several share it.
+        fn position(&self, name: &str) -> Option<usize> {
+            self.index
+                .get_or_init(|| {
+                    (self.nodes.iter().enumerate().rev())
+                        .map(|(idx, node)| (node.name.clone(), idx))
+                        .collect()
+                })
+                .get(name)
+                .copied()
+        }
+
+        // Appends a node, returning its position, without dropping the index.
+        fn add(&mut self, node: Node) -> usize {
+            let idx = self.nodes.len();
+            if let Some(index) = self.index.get_mut() {
+                index.entry(node.name.clone()).or_insert(idx);
+            }
+            self.nodes.push(node);
+            idx
+        }
+
+        // A node to edit without renaming it, which keeps the index.
+        fn node_mut(&mut self, idx: usize) -> &mut Node {
+            &mut self.nodes[idx]
+        }
+    }
+
+    impl Deref for NodeList {
+        type Target = Vec<Node>;
+
+        fn deref(&self) -> &Vec<Node> {
+            &self.nodes
+        }
+    }
+
+    impl DerefMut for NodeList {
+        fn deref_mut(&mut self) -> &mut Vec<Node> {
+            self.index.take();
+            &mut self.nodes
+        }
+    }
+
+    impl PartialEq for NodeList {
+        fn eq(&self, other: &Self) -> bool {
+            self.nodes == other.nodes
+        }
+    }
+
+    impl Eq for NodeList {}
+
+    impl PartialEq<Vec<Node>> for NodeList {
+        fn eq(&self, other: &Vec<Node>) -> bool {
+            &self.nodes == other
+        }
+    }
+
+    impl PartialEq<[Node]> for NodeList {
+        fn eq(&self, other: &[Node]) -> bool {
+            self.nodes == other
+        }
+    }
+
+    impl<const N: usize> PartialEq<[Node; N]> for NodeList {
+        fn eq(&self, other: &[Node; N]) -> bool {
+            self.nodes == other
+        }
+    }
+
+    impl fmt::Debug for NodeList {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            self.nodes.fmt(f)
+        }
+    }
+
+    impl<'a> IntoIterator for &'a NodeList {
+        type Item = &'a Node;
+        type IntoIter = std::slice::Iter<'a, Node>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            self.nodes.iter()
+        }
+    }
+
+    #[derive(Clone, PartialEq, Eq, Debug, Default)]
+    pub struct Graph {
+        pub kind: GraphKind,
+        // A strict graph has at most one edge between two nodes.
+        pub strict: bool,
+        pub name: Option<String>,
+        pub nodes: NodeList,
+        pub edges: Vec<Edge>,
+        subgraphs: Vec<Subgraph>,
+        pub attrs: HashMap<String, String>,
+        pub node_attrs: HashMap<String, String>,
+        pub edge_attrs: HashMap<String, String>,
+    }
+
+    impl Graph {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        pub fn with_kind(mut self, kind: GraphKind) -> Self {
+            self.kind = kind;
+            self
+        }
+
+        pub fn with_strict(mut self, strict: bool) -> Self {
+            self.strict = strict;
+            self
+        }
+
+        pub fn with_name(mut self, name: &str) -> Self {
+            self.name = Some(name.to_string());
+            self
+        }
+
+        pub fn from_dot(dot: &str) -> Result<Self, parser::ParseError> {
+            parser::parse(dot)
+        }
+
+        pub fn with_attrs(mut self, attrs: &[(&str, &str)]) -> Self {
+            for (key, value) in attrs {
+                self.attrs.insert(key.to_string(), value.to_string());
+            }
+            self
+        }
+
+        // Defaults for the attributes of every node of the graph.
+        pub fn with_node_attrs(mut self, attrs: &[(&str, &str)]) -> Self {
+            for (key, value) in attrs {
+                self.node_attrs.insert(key.to_string(), value.to_string());
+            }
+            self
+        }
+
+        // Defaults for the attributes of every edge of the graph.
+        pub fn with_edge_attrs(mut self, attrs: &[(&str, &str)]) -> Self {
+            for (key, value) in attrs {
+                self.edge_attrs.insert(key.to_string(), value.to_string());
+            }
+            self
+        }
+
+        // Replaces the edges of the graph, whether or not their nodes are in it.
+        pub fn with_edges(mut self, edges: &[Edge]) -> Self {
+            self.edges.clear();
+            for edge in edges {
+                self.push_edge(edge.clone());
+            }
+            self
+        }
+
+        // Replaces the edges of the graph, failing if one of them touches a node that is
+        // not in the graph.
+        pub fn try_with_edges(mut self, edges: &[Edge]) -> Result<Self, GraphError> {
+            self.edges.clear();
+            for edge in edges {
+                self.add_edge(edge.clone())?;
+            }
+            Ok(self)
+        }
+
+        // Replaces the nodes of the graph. A node named more than once gets the
+        // attributes of all of its mentions, the later ones taking precedence.
+        pub fn with_nodes(mut self, nodes: &[Node]) -> Self {
+            self.nodes.clear();
+            for node in nodes {
+                let idx = self.insert_node(&node.name);
+                self.nodes.node_mut(idx).attrs.extend(node.attrs.clone());
+            }
+            self
+        }
+
+        // Replaces the nodes of the graph, failing if two of them have the same name.
+        pub fn try_with_nodes(mut self, nodes: &[Node]) -> Result<Self, GraphError> {
+            self.nodes.clear();
+            for node in nodes {
+                self.add_node(node.clone())?;
+            }
+            Ok(self)
+        }
+
+        pub fn nodes(&self) -> &[Node] {
+            &self.nodes
+        }
+
+        pub fn edges(&self) -> &[Edge] {
+            &self.edges
+        }
+
+        pub fn node(&self, name: &str) -> Option<&Node> {
+            self.nodes.position(name).map(|idx| &self.nodes[idx])
+        }
+
+        pub fn contains_node(&self, name: &str) -> bool {
+            self.nodes.position(name).is_some()
+        }
+
+        pub fn add_node(&mut self, node: Node) -> Result<(), GraphError> {
+            if self.contains_node(&node.name) {
+                return Err(GraphError::DuplicateNode(node.name));
+            }
+            self.nodes.add(node);
+            Ok(())
+        }
+
+        pub fn add_edge(&mut self, edge: Edge) -> Result<(), GraphError> {
+            for name in [&edge.from, &edge.to] {
+                if !self.contains_node(name) {
+                    return Err(GraphError::UnknownNode(name.clone()));
+                }
+            }
+            self.push_edge(edge);
+            Ok(())
+        }
+
+        // Replaces the subgraphs of the graph, adding the nodes they name that are not in
+        // the graph yet.
+        pub fn with_subgraphs(mut self, subgraphs: &[Subgraph]) -> Self {
+            self.subgraphs = subgraphs.to_vec();
+            let names: Vec<String> = subgraphs
+                .iter()
+                .flat_map(|sub| sub.node_names())
+                .map(|name| name.to_string())
+                .collect();
+            for name in names {
+                self.insert_node(&name);
+            }
+            self
+        }
+
+        pub fn subgraphs(&self) -> &[Subgraph] {
+            &self.subgraphs
+        }
+
+        // The subgraph with the given name, however deeply it is nested.
+        pub fn subgraph(&self, name: &str) -> Option<&Subgraph> {
+            fn find<'a>(subgraphs: &'a [Subgraph], name: &str) -> Option<&'a Subgraph> {
+                subgraphs
+                    .iter()
+                    .find_map(|sub| match sub.name.as_deref() == Some(name) {
+                        true => Some(sub),
+                        false => find(&sub.subgraphs, name),
+                    })
+            }
+            find(&self.subgraphs, name)
+        }
+
+        // An attribute of a node, falling back on the defaults of the subgraphs holding
+        // it, the innermost first, then on the defaults of the graph. Of several sibling
+        // subgraphs holding the node, the first one counts, as DOT creates it there.
+        pub fn node_attr(&self, name: &str, key: &str) -> Option<&str> {
+            let node = self.node(name)?;
+            node.attr(key).or_else(|| {
+                let path = holder(&self.subgraphs, &|sub| sub.contains(name));
+                self.inherited(Defaults::Node, &path, key)
+                    .map(|(_, value)| value)
+            })
+        }
+
+        // An attribute of an edge, falling back on the defaults of the subgraphs holding
+        // both of its ends, the innermost first, then on the defaults of the graph.
+        pub fn edge_attr<'a>(&'a self, edge: &'a Edge, key: &str) -> Option<&'a str> {
+            edge.attr(key).or_else(|| {
+                let path = holder(&self.subgraphs, &|sub| holds_edge(sub, edge));
+                self.inherited(Defaults::Edge, &path, key)
+                    .map(|(_, value)| value)
+            })
+        }
+
+        // The default for `key` set along the subgraphs at `path`, the innermost first and
+        // the graph last, with the depth at which it is set.
+        fn inherited(
+            &self,
+            defaults: Defaults,
+            path: &[usize],
+            key: &str,
+        ) -> Option<(usize, &str)> {
+            let mut found = defaults.of_graph(self).get(key).map(|v| (0, v.as_str()));
+            let mut subgraphs = &self.subgraphs;
+            for (depth, &idx) in path.iter().enumerate() {
+                if let Some(value) = defaults.of(&subgraphs[idx]).get(key) {
+                    found = Some((depth + 1, value.as_str()));
+                }
+                subgraphs = &subgraphs[idx].subgraphs;
+            }
+            found
+        }
+
+        // Makes every node and edge inherit the defaults it was given when it was created,
+        // as DOT applies `node [...]` and `edge [...]` only to what comes after them.
+        // `node_defaults` holds the defaults each node was created with and
+        // `edge_defaults` those of every edge. A default that would also reach items
+        // created before it is taken off where it is set, the items given it keeping it
+        // as an attribute of their own.
+        fn settle_defaults(
+            &mut self,
+            node_defaults: &HashMap<String, HashMap<String, String>>,
+            edge_defaults: &[HashMap<String, String>],
+        ) {
+            loop {
+                let mut fixes = Vec::new();
+                for (idx, node) in self.nodes.iter().enumerate() {
+                    if let Some(given) = node_defaults.get(&node.name) {
+                        let path = holder(&self.subgraphs, &|sub| sub.contains(&node.name));
+                        self.check_defaults(
+                            Defaults::Node,
+                            idx,
+                            &path,
+                            &node.attrs,
+                            given,
+                            &mut fixes,
+                        );
+                    }
+                }
+                for (idx, (edge, given)) in self.edges.iter().zip(edge_defaults).enumerate() {
+                    let path = holder(&self.subgraphs, &|sub| holds_edge(sub, edge));
+                    self.check_defaults(Defaults::Edge, idx, &path, &edge.attrs, given, &mut fixes);
+                }
+                if fixes.is_empty() {
+                    return;
+                }
+                for fix in fixes {
+                    match fix {
+                        DefaultFix::Keep {
+                            defaults: Defaults::Node,
+                            idx,
+                            key,
+                            value,
+                        } => {
+                            self.nodes.node_mut(idx).attrs.insert(key, value);
+                        }
+                        DefaultFix::Keep {
+                            defaults: Defaults::Edge,
+                            idx,
+                            key,
+                            value,
+                        } => {
+                            self.edges[idx].attrs.insert(key, value);
+                        }
+                        DefaultFix::Drop {
+                            defaults,
+                            path,
+                            key,
+                        } => {
+                            defaults.of_mut(self, &path).remove(&key);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Compares the defaults an item inherits with the ones it was `given`.
+        fn check_defaults(
+            &self,
+            defaults: Defaults,
+            idx: usize,
+            path: &[usize],
+            attrs: &HashMap<String, String>,
+            given: &HashMap<String, String>,
+            fixes: &mut Vec<DefaultFix>,
+        ) {
+            let mut keys: Vec<&String> = given.keys().collect();
+            keys.extend(defaults.of_graph(self).keys());
+            let mut subgraphs = &self.subgraphs;
+            for &sub in path {
+                keys.extend(defaults.of(&subgraphs[sub]).keys());
+                subgraphs = &subgraphs[sub].subgraphs;
+            }
+            keys.sort();
+            keys.dedup();
+            for key in keys.into_iter().filter(|key| !attrs.contains_key(*key)) {
+                let inherited = self.inherited(defaults, path, key);
+                match (given.get(key), inherited) {
+                    (Some(value), Some((_, found))) if value == found => {}
+                    (Some(value), _) => fixes.push(DefaultFix::Keep {
+                        defaults,
+                        idx,
+                        key: key.clone(),
+                        value: value.clone(),
+                    }),
+                    (None, Some((depth, _))) => fixes.push(DefaultFix::Drop {
+                        defaults,
+                        path: path[..depth].to_vec(),
+                        key: key.clone(),
+                    }),
+                    (None, None) => {}
+                }
+            }
+        }
+
+        // Removes a node along with every edge touching it.
+        pub fn remove_node(&mut self, name: &str) -> Option<Node> {
+            let idx = self.nodes.position(name)?;
+            let node = self.nodes.remove(idx);
+            self.edges
+                .retain(|edge| edge.from != name && edge.to != name);
+            visit_subgraphs(&mut self.subgraphs, &mut |sub| {
+                sub.nodes.retain(|member| member != name)
+            });
+            Some(node)
+        }
+
+        // Gives a node a new name, which the edges touching it follow.
+        pub fn rename_node(&mut self, name: &str, new_name: &str) -> Result<(), GraphError> {
+            let idx = self
+                .nodes
+                .position(name)
+                .ok_or_else(|| GraphError::UnknownNode(name.to_string()))?;
+            if name == new_name {
+                return Ok(());
+            }
+            if self.contains_node(new_name) {
+                return Err(GraphError::DuplicateNode(new_name.to_string()));
+            }
+            self.nodes[idx].name = new_name.to_string();
+            for edge in &mut self.edges {
+                if edge.from == name {
+                    edge.from = new_name.to_string();
+                }
+                if edge.to == name {
+                    edge.to = new_name.to_string();
+                }
+            }
+            visit_subgraphs(&mut self.subgraphs, &mut |sub| {
+                for member in sub.nodes.iter_mut().filter(|member| *member == name) {
+                    *member = new_name.to_string();
+                }
+            });
+            Ok(())
+        }
+
+        // Adds an edge, merging it into the existing one between the same nodes when the
+        // graph is strict.
+        fn push_edge(&mut self, edge: Edge) {
+            let undirected = self.kind == GraphKind::Undirected;
+            let existing = self.strict.then(|| {
+                self.edges.iter_mut().find(|other| {
+                    (other.from == edge.from && other.to == edge.to)
+                        || (undirected && other.from == edge.to && other.to == edge.from)
+                })
+            });
+            match existing.flatten() {
+                Some(other) => other.attrs.extend(edge.attrs),
+                None => self.edges.push(edge),
+            }
+        }
+
+        // Position of the node with the given name, which is added without attributes
+        // if it is not in the graph yet.
+        fn insert_node(&mut self, name: &str) -> usize {
+            match self.nodes.position(name) {
+                Some(idx) => idx,
+                None => self.nodes.add(Node::new(name)),
+            }
+        }
+
+        pub fn attr(&self, key: &str) -> Option<&str> {
+            self.attrs.get(key).map(|v| v.as_str())
+        }
+
+        // Writes the graph in the DOT language. Nodes and edges keep their order, while
+        // the attributes are sorted by key so that the output is deterministic. Each edge
+        // is written in the innermost subgraph holding both of its ends, so that it
+        // inherits the same defaults when the output is read back.
+        pub fn to_dot(&self) -> String {
+            let mut dot = String::new();
+            if self.strict {
+                dot.push_str("strict ");
+            }
+            dot.push_str(&format!("{} ", self.kind.keyword()));
+            if let Some(name) = &self.name {
+                dot.push_str(&format!("{} ", writer::id(name)));
+            }
+            dot.push_str("{\n");
+            writer::defaults(&mut dot, 1, &self.attrs, &self.node_attrs, &self.edge_attrs);
+            let edges: Vec<_> = self
+                .edges
+                .iter()
+                .map(|edge| (holder(&self.subgraphs, &|sub| holds_edge(sub, edge)), edge))
+                .collect();
+            let op = self.kind.edge_op();
+            // Subgraphs come before the nodes, so that their defaults reach their nodes
+            // when the output is read back.
+            for (idx, sub) in self.subgraphs.iter().enumerate() {
+                writer::subgraph(&mut dot, 1, sub, &mut vec![idx], &edges, op);
+            }
+            for node in &self.nodes {
+                writer::node(&mut dot, 1, &node.name, &node.attrs);
+            }
+            for (_, edge) in edges.iter().filter(|(path, _)| path.is_empty()) {
+                writer::edge(&mut dot, 1, edge, op);
+            }
+            dot.push('}');
+            dot
+        }
+    }
+
+    impl FromStr for Graph {
+        type Err = parser::ParseError;
+
+        fn from_str(dot: &str) -> Result<Self, Self::Err> {
+            parser::parse(dot)
+        }
+    }
+
+    impl fmt::Display for Graph {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.to_dot())
+        }
+    }
+
+    // Which of the `node [...]` and `edge [...]` defaults to look at.
+    #[derive(Clone, Copy)]
+    enum Defaults {
+        Node,
+        Edge,
+    }
+
+    impl Defaults {
+        fn of(self, sub: &Subgraph) -> &HashMap<String, String> {
+            match self {
+                Defaults::Node => &sub.node_attrs,
+                Defaults::Edge => &sub.edge_attrs,
+            }
+        }
+
+        fn of_graph(self, graph: &Graph) -> &HashMap<String, String> {
+            match self {
+                Defaults::Node => &graph.node_attrs,
+                Defaults::Edge => &graph.edge_attrs,
+            }
+        }
+
+        // The defaults of the subgraph at `path`, or of the graph for an empty path.
+        fn of_mut<'a>(
+            self,
+            graph: &'a mut Graph,
+            path: &[usize],
+        ) -> &'a mut HashMap<String, String> {
+            let Some((&first, rest)) = path.split_first() else {
+                return match self {
+                    Defaults::Node => &mut graph.node_attrs,
+                    Defaults::Edge => &mut graph.edge_attrs,
+                };
+            };
+            let sub = rest.iter().fold(&mut graph.subgraphs[first], |sub, &idx| {
+                &mut sub.subgraphs[idx]
+            });
+            match self {
+                Defaults::Node => &mut sub.node_attrs,
+                Defaults::Edge => &mut sub.edge_attrs,
+            }
+        }
+    }
+
+    // A change settling the defaults of a graph, either giving the item at `idx` an
+    // attribute of its own or taking a default off the subgraph at `path`.
+    enum DefaultFix {
+        Keep {
+            defaults: Defaults,
+            idx: usize,
+            key: String,
+            value: String,
+        },
+        Drop {
+            defaults: Defaults,
+            path: Vec<usize>,
+            key: String,
+        },
+    }
+
+    // Indices leading to the innermost subgraph matching `holds`, following the first
+    // match at every level.
+    fn holder(subgraphs: &[Subgraph], holds: &dyn Fn(&Subgraph) -> bool) -> Vec<usize> {
+        match subgraphs.iter().position(holds) {
+            Some(idx) => {
+                let mut path = vec![idx];
+                path.extend(holder(&subgraphs[idx].subgraphs, holds));
+                path
+            }
+            None => Vec::new(),
+        }
+    }
+
+    // Whether both ends of the edge are in the subgraph, which its defaults then reach.
+    fn holds_edge(sub: &Subgraph, edge: &Edge) -> bool {
+        sub.contains(&edge.from) && sub.contains(&edge.to)
+    }
+
+    fn visit_subgraphs(subgraphs: &mut [Subgraph], visit: &mut dyn FnMut(&mut Subgraph)) {
+        for sub in subgraphs {
+            visit(sub);
+            visit_subgraphs(&mut sub.subgraphs, visit);
+        }
+    }
+
+    mod writer {
+        use super::graph_items::edge::Edge;
+        use super::graph_items::subgraph::Subgraph;
+        use std::borrow::Cow;
+        use std::collections::HashMap;
+
+        const KEYWORDS: [&str; 6] = ["node", "edge", "graph", "digraph", "subgraph", "strict"];
+
+        fn is_identifier(id: &str) -> bool {
+            let mut chars = id.chars();
+            match chars.next() {
+                Some(c) if c.is_ascii_alphabetic() || c == '_' || !c.is_ascii() => {}
+                _ => return false,
+            }
+            chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || !c.is_ascii())
+                && !KEYWORDS.iter().any(|k| k.eq_ignore_ascii_case(id))
+        }
+
+        fn is_numeral(id: &str) -> bool {
+            let digits = id.strip_prefix('-').unwrap_or(id);
+            let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+            !(whole.is_empty() && fraction.is_empty())
+                && whole.chars().all(|c| c.is_ascii_digit())
+                && fraction.chars().all(|c| c.is_ascii_digit())
+                && (digits.contains('.') || !whole.is_empty())
+        }
+
+        // HTML-like IDs are written between angle brackets instead of quotes. The outer
+        // brackets must enclose the whole ID, so `<a><b>` has to be quoted.
+        fn is_html(id: &str) -> bool {
+            let mut depth = 0i32;
+            let last = id.chars().count().saturating_sub(1);
+            id.starts_with('<')
+                && id.chars().enumerate().all(|(idx, c)| {
+                    match c {
+                        '<' => depth += 1,
+                        '>' => depth -= 1,
+                        _ => {}
+                    }
+                    if idx == last {
+                        depth == 0
+                    } else {
+                        depth > 0
+                    }
+                })
+        }
+
+        // An ID as it must be written in DOT, quoted unless it is a plain identifier, a
+        // numeral or an HTML-like string.
+        pub fn id(id: &str) -> Cow<'_, str> {
+            if is_identifier(id) || is_numeral(id) || is_html(id) {
+                return Cow::Borrowed(id);
+            }
+            let mut quoted = String::with_capacity(id.len() + 2);
+            quoted.push('"');
+            let mut chars = id.chars().peekable();
+            while let Some(c) = chars.next() {
+                match c {
+                    '"' => quoted.push_str("\\\""),
+                    // Backslashes are kept for escapes such as `\l`, but doubled where the
+                    // parser would otherwise read them as part of an escape or as a
+                    // line continuation.
+                    '\\' if matches!(chars.peek(), None | Some('\\' | '"' | '\n')) => {
+                        quoted.push_str("\\\\")
+                    }
+                    _ => quoted.push(c),
+                }
+            }
+            quoted.push('"');
+            Cow::Owned(quoted)
+        }
+
+        pub fn attr_list(attrs: &HashMap<String, String>) -> String {
+            let mut keys: Vec<_> = attrs.keys().collect();
+            keys.sort();
+            let attrs: Vec<_> = keys
+                .into_iter()
+                .map(|key| format!("{}={}", id(key), id(&attrs[key])))
+                .collect();
+            format!("[{}]", attrs.join(", "))
+        }
+
+        // The `graph`, `node` and `edge` attribute statements of a graph or subgraph,
+        // leaving out the empty ones.
+        pub fn defaults(
+            dot: &mut String,
+            depth: usize,
+            attrs: &HashMap<String, String>,
+            node_attrs: &HashMap<String, String>,
+            edge_attrs: &HashMap<String, String>,
+        ) {
+            let indent = "    ".repeat(depth);
+            for (keyword, attrs) in [("graph", attrs), ("node", node_attrs), ("edge", edge_attrs)] {
+                if !attrs.is_empty() {
+                    dot.push_str(&format!("{}{} {}\n", indent, keyword, attr_list(attrs)));
+                }
+            }
+        }
+
+        pub fn node(dot: &mut String, depth: usize, name: &str, attrs: &HashMap<String, String>) {
+            dot.push_str(&format!("{}{}", "    ".repeat(depth), id(name)));
+            if !attrs.is_empty() {
+                dot.push_str(&format!(" {}", attr_list(attrs)));
+            }
+            dot.push('\n');
+        }
+
+        pub fn edge(dot: &mut String, depth: usize, edge: &Edge, op: &str) {
+            let (from, to) = (id(&edge.from), id(&edge.to));
+            dot.push_str(&format!("{}{} {} {}", "    ".repeat(depth), from, op, to));
+            if !edge.attrs.is_empty() {
+                dot.push_str(&format!(" {}", attr_list(&edge.attrs)));
+            }
+            dot.push('\n');
+        }
+
+        // A subgraph with the edges held by it, which are given with the path of the
+        // subgraph holding them. Nested subgraphs come first for their defaults to reach
+        // their nodes, and the edges last for all their ends to be in the subgraph.
+        pub fn subgraph(
+            dot: &mut String,
+            depth: usize,
+            sub: &Subgraph,
+            path: &mut Vec<usize>,
+            edges: &[(Vec<usize>, &Edge)],
+            op: &str,
+        ) {
+            let indent = "    ".repeat(depth);
+            dot.push_str(&format!("{}subgraph ", indent));
+            if let Some(name) = &sub.name {
+                dot.push_str(&format!("{} ", id(name)));
+            }
+            dot.push_str("{\n");
+            defaults(dot, depth + 1, &sub.attrs, &sub.node_attrs, &sub.edge_attrs);
+            for (idx, nested) in sub.subgraphs.iter().enumerate() {
+                path.push(idx);
+                subgraph(dot, depth + 1, nested, path, edges, op);
+                path.pop();
+            }
+            for name in &sub.nodes {
+                node(dot, depth + 1, name, &HashMap::new());
+            }
+            for (_, held) in edges.iter().filter(|(held_by, _)| held_by == path) {
+                edge(dot, depth + 1, held, op);
+            }
+            dot.push_str(&format!("{}}}\n", indent));
+        }
+    }
+
+    pub mod parser {
+        use super::graph_items::edge::Edge;
+        use super::graph_items::node::Node;
+        use super::graph_items::subgraph::Subgraph;
+        use super::{Defaults, Graph, GraphKind};
+        use std::collections::HashMap;
+
+        #[derive(Debug, PartialEq, Eq, Clone)]
+        pub enum ParseErrorKind {
+            UnexpectedChar(char),
+            UnterminatedString,
+            UnterminatedHtml,
+            UnterminatedComment,
+            UnexpectedToken {
+                expected: &'static str,
+                found: String,
+            },
+            UnexpectedEnd {
+                expected: &'static str,
+            },
+        }
+
+        // Where the DOT input is malformed, `line` and `column` counting from 1.
+        #[derive(Debug, PartialEq, Eq, Clone)]
+        pub struct ParseError {
+            pub line: usize,
+            pub column: usize,
+            pub kind: ParseErrorKind,
+        }
+
+        #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+        enum Keyword {
+            Strict,
+            Graph,
+            Digraph,
+            Node,
+            Edge,
+            Subgraph,
+        }
+
+        #[derive(Clone, PartialEq, Eq, Debug)]
+        enum Token {
+            Id(String),
+            Quoted(String),
+            Html(String),
+            Keyword(Keyword),
+            LeftBrace,
+            RightBrace,
+            LeftBracket,
+            RightBracket,
+            Equal,
+            Semicolon,
+            Comma,
+            Colon,
+            Plus,
+            DirectedEdge,
+            UndirectedEdge,
+        }
+
+        impl Token {
+            fn describe(&self) -> String {
+                match self {
+                    Token::Id(id) | Token::Quoted(id) | Token::Html(id) => {
+                        format!("ID `{}`", id)
+                    }
+                    Token::Keyword(keyword) => {
+                        format!("keyword `{}`", format!("{:?}", keyword).to_lowercase())
+                    }
+                    Token::LeftBrace => "`{`".to_string(),
+                    Token::RightBrace => "`}`".to_string(),
+                    Token::LeftBracket => "`[`".to_string(),
+                    Token::RightBracket => "`]`".to_string(),
+                    Token::Equal => "`=`".to_string(),
+                    Token::Semicolon => "`;`".to_string(),
+                    Token::Comma => "`,`".to_string(),
+                    Token::Colon => "`:`".to_string(),
+                    Token::Plus => "`+`".to_string(),
+                    Token::DirectedEdge => "`->`".to_string(),
+                    Token::UndirectedEdge => "`--`".to_string(),
+                }
+            }
+        }
+
+        type Position = (usize, usize);
+
+        struct Lexer {
+            chars: Vec<char>,
+            idx: usize,
+            line: usize,
+            column: usize,
+        }
+
+        impl Lexer {
+            fn new(input: &str) -> Self {
+                Self {
+                    chars: input.chars().collect(),
+                    idx: 0,
+                    line: 1,
+                    column: 1,
+                }
+            }
+
+            fn peek(&self) -> Option<char> {
+                self.chars.get(self.idx).copied()
+            }
+
+            fn peek_next(&self) -> Option<char> {
+                self.chars.get(self.idx + 1).copied()
+            }
+
+            fn bump(&mut self) -> Option<char> {
+                let c = self.peek()?;
+                self.idx += 1;
+                if c == '\n' {
+                    self.line += 1;
+                    self.column = 1;
+                } else {
+                    self.column += 1;
+                }
+                Some(c)
+            }
+
+            fn error(&self, (line, column): Position, kind: ParseErrorKind) -> ParseError {
+                ParseError { line, column, kind }
+            }
+
+            // Whether only whitespace precedes the current character on its line, where
+            // a `#` starts a line to be discarded.
+            fn at_line_start(&self) -> bool {
+                self.chars[..self.idx]
+                    .iter()
+                    .rev()
+                    .take_while(|c| **c != '\n')
+                    .all(|c| c.is_whitespace())
+            }
+
+            fn tokenize(mut self) -> Result<(Vec<(Token, Position)>, Position), ParseError> {
+                let mut tokens = Vec::new();
+                while let Some(c) = self.peek() {
+                    let start = (self.line, self.column);
+                    let token = match c {
+                        _ if c.is_whitespace() => {
+                            self.bump();
+                            continue;
+                        }
+                        '/' if self.peek_next() == Some('/') => {
+                            while self.peek().is_some_and(|c| c != '\n') {
+                                self.bump();
+                            }
+                            continue;
+                        }
+                        '#' if self.at_line_start() => {
+                            while self.peek().is_some_and(|c| c != '\n') {
+                                self.bump();
+                            }
+                            continue;
+                        }
+                        '/' if self.peek_next() == Some('*') => {
+                            self.bump();
+                            self.bump();
+                            loop {
+                                match self.bump() {
+                                    Some('*') if self.peek() == Some('/') => break,
+                                    Some(_) => {}
+                                    None => {
+                                        return Err(
+                                            self.error(start, ParseErrorKind::UnterminatedComment)
+                                        )
+                                    }
+                                }
+                            }
+                            self.bump();
+                            continue;
+                        }
+                        '{' => self.punctuation(Token::LeftBrace),
+                        '}' => self.punctuation(Token::RightBrace),
+                        '[' => self.punctuation(Token::LeftBracket),
+                        ']' => self.punctuation(Token::RightBracket),
+                        '=' => self.punctuation(Token::Equal),
+                        ';' => self.punctuation(Token::Semicolon),
+                        ',' => self.punctuation(Token::Comma),
+                        ':' => self.punctuation(Token::Colon),
+                        '+' => self.punctuation(Token::Plus),
+                        '-' if self.peek_next() == Some('>') => {
+                            self.bump();
+                            self.punctuation(Token::DirectedEdge)
+                        }
+                        '-' if self.peek_next() == Some('-') => {
+                            self.bump();
+                            self.punctuation(Token::UndirectedEdge)
+                        }
+                        '-' | '.' | '0'..='9' => self.numeral(start)?,
+                        '"' => self.quoted(start)?,
+                        '<' => self.html(start)?,
+                        _ if c.is_alphabetic() || c == '_' || !c.is_ascii() => self.identifier(),
+                        _ => return Err(self.error(start, ParseErrorKind::UnexpectedChar(c))),
+                    };
+                    tokens.push((token, start));
+                }
+                Ok((tokens, (self.line, self.column)))
+            }
+
+            fn punctuation(&mut self, token: Token) -> Token {
+                self.bump();
+                token
+            }
+
+            fn numeral(&mut self, start: Position) -> Result<Token, ParseError> {
+                let mut numeral = String::new();
+                if self.peek() == Some('-') {
+                    numeral.push('-');
+                    self.bump();
+                }
+                let mut seen_dot = false;
+                let mut seen_digit = false;
+                while let Some(c) = self.peek() {
+                    match c {
+                        '0'..='9' => seen_digit = true,
+                        '.' if !seen_dot => seen_dot = true,
+                        _ => break,
+                    }
+                    numeral.push(c);
+                    self.bump();
+                }
+                match self.peek() {
+                    Some(c) if !seen_digit => {
+                        Err(self.error(start, ParseErrorKind::UnexpectedChar(c)))
+                    }
+                    None if !seen_digit => Err(self.error(
+                        start,
+                        ParseErrorKind::UnexpectedEnd {
+                            expected: "a digit",
+                        },
+                    )),
+                    // A numeral running into a letter, such as `2nd`, is not a valid ID.
+                    Some(c) if c.is_alphabetic() || c == '_' => {
+                        Err(self.error((self.line, self.column), ParseErrorKind::UnexpectedChar(c)))
+                    }
+                    _ => Ok(Token::Id(numeral)),
+                }
+            }
+
+            fn identifier(&mut self) -> Token {
+                let mut id = String::new();
+                while let Some(c) = self.peek() {
+                    if !(c.is_alphanumeric() || c == '_' || !c.is_ascii()) {
+                        break;
+                    }
+                    id.push(c);
+                    self.bump();
+                }
+                let keyword = match id.to_lowercase().as_str() {
+                    "strict" => Keyword::Strict,
+                    "graph" => Keyword::Graph,
+                    "digraph" => Keyword::Digraph,
+                    "node" => Keyword::Node,
+                    "edge" => Keyword::Edge,
+                    "subgraph" => Keyword::Subgraph,
+                    _ => return Token::Id(id),
+                };
+                Token::Keyword(keyword)
+            }
+
+            // A double-quoted string, in which `\"` stands for a quote, `\\` for a
+            // backslash and a backslash before a newline continues the string on the next
+            // line. Any other backslash is kept, as in `\l` which is meaningful to Graphviz.
+            fn quoted(&mut self, start: Position) -> Result<Token, ParseError> {
+                self.bump();
+                let mut quoted = String::new();
+                loop {
+                    match self.bump() {
+                        Some('"') => return Ok(Token::Quoted(quoted)),
+                        Some('\\') if matches!(self.peek(), Some('"' | '\\')) => {
+                            quoted.push(self.bump().unwrap());
+                        }
+                        Some('\\') if self.peek() == Some('\n') => {
+                            self.bump();
+                        }
+                        Some(c) => quoted.push(c),
+                        None => return Err(self.error(start, ParseErrorKind::UnterminatedString)),
+                    }
+                }
+            }
+
+            // An HTML-like string, kept with its outer angle brackets.
+            fn html(&mut self, start: Position) -> Result<Token, ParseError> {
+                let mut html = String::new();
+                let mut depth = 0;
+                loop {
+                    match self.bump() {
+                        Some(c) => {
+                            html.push(c);
+                            match c {
+                                '<' => depth += 1,
+                                '>' => depth -= 1,
+                                _ => {}
+                            }
+                            if depth == 0 {
+                                return Ok(Token::Html(html));
+                            }
+                        }
+                        None => return Err(self.error(start, ParseErrorKind::UnterminatedHtml)),
+                    }
+                }
+            }
+        }
+
+        // Defaults set by `node [...]` and `edge [...]` so far, applying to the nodes and
+        // edges created afterwards in the same graph or subgraph.
+        #[derive(Clone, Default)]
+        struct Scope {
+            node_attrs: HashMap<String, String>,
+            edge_attrs: HashMap<String, String>,
+            // Nodes mentioned in the scope, making up the subgraph when it is used as the
+            // end of an edge.
+            nodes: Vec<String>,
+            // The subgraph as it will be kept in the graph, with the nodes mentioned
+            // directly in it rather than in its own subgraphs.
+            subgraph: Subgraph,
+        }
+
+        struct Parser {
+            tokens: Vec<(Token, Position)>,
+            idx: usize,
+            end: Position,
+            graph: Graph,
+            scopes: Vec<Scope>,
+            // The defaults in scope when each node and edge was created.
+            node_defaults: HashMap<String, HashMap<String, String>>,
+            edge_defaults: Vec<HashMap<String, String>>,
+        }
+
+        // Parses a graph written in the DOT language. The defaults set by `node [...]` and
+        // `edge [...]` are kept on the graph or subgraph setting them, to be inherited by
+        // its nodes and edges. As they do not apply to the nodes and edges created before
+        // them, those are settled once the whole graph is read. Subgraphs are kept when
+        // they have a name, attributes or defaults, anonymous ones only grouping their
+        // nodes into their parent.
+        pub fn parse(input: &str) -> Result<Graph, ParseError> {
+            let (tokens, end) = Lexer::new(input).tokenize()?;
+            let mut parser = Parser {
+                tokens,
+                idx: 0,
+                end,
+                graph: Graph::new(),
+                scopes: vec![Scope::default()],
+                node_defaults: HashMap::new(),
+                edge_defaults: Vec::new(),
+            };
+            parser.graph_definition()?;
+            let top = parser.scopes.pop().unwrap();
+            let mut graph = parser.graph;
+            graph.subgraphs = top.subgraph.subgraphs;
+            graph.settle_defaults(&parser.node_defaults, &parser.edge_defaults);
+            Ok(graph)
+        }
+
+        impl Parser {
+            fn peek(&self) -> Option<&Token> {
+                self.tokens.get(self.idx).map(|(token, _)| token)
+            }
+
+            fn peek_at(&self, offset: usize) -> Option<&Token> {
+                self.tokens.get(self.idx + offset).map(|(token, _)| token)
+            }
+
+            fn error(&self, expected: &'static str) -> ParseError {
+                match self.tokens.get(self.idx) {
+                    Some((token, (line, column))) => ParseError {
+                        line: *line,
+                        column: *column,
+                        kind: ParseErrorKind::UnexpectedToken {
+                            expected,
+                            found: token.describe(),
+                        },
+                    },
+                    None => ParseError {
+                        line: self.end.0,
+                        column: self.end.1,
+                        kind: ParseErrorKind::UnexpectedEnd { expected },
+                    },
+                }
+            }
+
+            fn eat(&mut self, token: &Token) -> bool {
+                let matches = self.peek() == Some(token);
+                if matches {
+                    self.idx += 1;
+                }
+                matches
+            }
+
+            fn expect(&mut self, token: Token, expected: &'static str) -> Result<(), ParseError> {
+                if self.eat(&token) {
+                    Ok(())
+                } else {
+                    Err(self.error(expected))
+                }
+            }
+
+            fn is_id(&self) -> bool {
+                matches!(
+                    self.peek(),
+                    Some(Token::Id(_) | Token::Quoted(_) | Token::Html(_))
+                )
+            }
+
+            fn id(&mut self) -> Result<String, ParseError> {
+                let id = match self.peek() {
+                    Some(Token::Id(id) | Token::Html(id)) => id.clone(),
+                    Some(Token::Quoted(quoted)) => {
+                        let mut quoted = quoted.clone();
+                        // Quoted strings may be concatenated with `+`.
+                        while self.peek_at(1) == Some(&Token::Plus) {
+                            self.idx += 2;
+                            match self.peek() {
+                                Some(Token::Quoted(next)) => quoted.push_str(next),
+                                _ => return Err(self.error("a quoted string")),
+                            }
+                        }
+                        quoted
+                    }
+                    _ => return Err(self.error("an ID")),
+                };
+                self.idx += 1;
+                Ok(id)
+            }
+
+            fn graph_definition(&mut self) -> Result<(), ParseError> {
+                self.graph.strict = self.eat(&Token::Keyword(Keyword::Strict));
+                self.graph.kind = match self.peek() {
+                    Some(Token::Keyword(Keyword::Graph)) => GraphKind::Undirected,
+                    Some(Token::Keyword(Keyword::Digraph)) => GraphKind::Directed,
+                    _ => return Err(self.error("`graph` or `digraph`")),
+                };
+                self.idx += 1;
+                if self.is_id() {
+                    self.graph.name = Some(self.id()?);
+                }
+                self.expect(Token::LeftBrace, "`{`")?;
+                self.statements()?;
+                self.expect(Token::RightBrace, "`}`")?;
+                match self.peek() {
+                    Some(_) => Err(self.error("end of input")),
+                    None => Ok(()),
+                }
+            }
+
+            fn statements(&mut self) -> Result<(), ParseError> {
+                while !matches!(self.peek(), Some(Token::RightBrace) | None) {
+                    self.statement()?;
+                    self.eat(&Token::Semicolon);
+                }
+                Ok(())
+            }
+
+            fn statement(&mut self) -> Result<(), ParseError> {
+                match self.peek() {
+                    Some(Token::Keyword(Keyword::Graph)) => {
+                        self.idx += 1;
+                        let attrs = self.attr_lists()?;
+                        self.attrs().extend(attrs);
+                    }
+                    Some(Token::Keyword(Keyword::Node)) => {
+                        self.idx += 1;
+                        let attrs = self.attr_lists()?;
+                        self.scope().node_attrs.extend(attrs.clone());
+                        self.defaults(Defaults::Node).extend(attrs);
+                    }
+                    Some(Token::Keyword(Keyword::Edge)) => {
+                        self.idx += 1;
+                        let attrs = self.attr_lists()?;
+                        self.scope().edge_attrs.extend(attrs.clone());
+                        self.defaults(Defaults::Edge).extend(attrs);
+                    }
+                    Some(Token::Keyword(Keyword::Subgraph) | Token::LeftBrace) => {
+                        let nodes = self.subgraph()?;
+                        self.edges(nodes)?;
+                    }
+                    _ if self.is_id() && self.peek_at(1) == Some(&Token::Equal) => {
+                        let key = self.id()?;
+                        self.idx += 1;
+                        let value = self.id()?;
+                        self.attrs().insert(key, value);
+                    }
+                    _ if self.is_id() => {
+                        let (name, port) = self.node_id()?;
+                        if self.is_edge_op() {
+                            self.edges(vec![(name, port)])?;
+                        } else {
+                            let attrs = self.attr_lists()?;
+                            self.node(&name).attrs.extend(attrs);
+                        }
+                    }
+                    _ => return Err(self.error("a statement")),
+                }
+                Ok(())
+            }
+
+            fn scope(&mut self) -> &mut Scope {
+                self.scopes.last_mut().unwrap()
+            }
+
+            // Attributes of the graph or subgraph being parsed.
+            fn attrs(&mut self) -> &mut HashMap<String, String> {
+                match self.scopes.len() {
+                    1 => &mut self.graph.attrs,
+                    _ => &mut self.scope().subgraph.attrs,
+                }
+            }
+
+            // Defaults kept on the graph or subgraph being parsed.
+            fn defaults(&mut self, defaults: Defaults) -> &mut HashMap<String, String> {
+                if self.scopes.len() == 1 {
+                    return defaults.of_mut(&mut self.graph, &[]);
+                }
+                let subgraph = &mut self.scope().subgraph;
+                match defaults {
+                    Defaults::Node => &mut subgraph.node_attrs,
+                    Defaults::Edge => &mut subgraph.edge_attrs,
+                }
+            }
+
+            fn is_edge_op(&self) -> bool {
+                matches!(
+                    self.peek(),
+                    Some(Token::DirectedEdge | Token::UndirectedEdge)
+                )
+            }
+
+            // Zero or more attribute lists, e.g. `[color=red, shape=box][label=A]`.
+            fn attr_lists(&mut self) -> Result<HashMap<String, String>, ParseError> {
+                let mut attrs = HashMap::new();
+                while self.eat(&Token::LeftBracket) {
+                    while !self.eat(&Token::RightBracket) {
+                        if !self.is_id() {
+                            return Err(self.error("an attribute or `]`"));
+                        }
+                        let key = self.id()?;
+                        self.expect(Token::Equal, "`=`")?;
+                        let value = self.id()?;
+                        attrs.insert(key, value);
+                        if !self.eat(&Token::Comma) {
+                            self.eat(&Token::Semicolon);
+                        }
+                    }
+                }
+                Ok(attrs)
+            }
+
+            // A node ID with an optional port, e.g. `a:n` or `a:p1:sw`.
+            fn node_id(&mut self) -> Result<(String, Option<String>), ParseError> {
+                let name = self.id()?;
+                let mut port = None;
+                if self.eat(&Token::Colon) {
+                    let mut value = self.id()?;
+                    if self.eat(&Token::Colon) {
+                        value = format!("{}:{}", value, self.id()?);
+                    }
+                    port = Some(value);
+                }
+                self.node(&name);
+                Ok((name, port))
+            }
+
+            // The node with the given name, created when it is mentioned for the first time.
+            fn node(&mut self, name: &str) -> &mut Node {
+                let scope = self.scopes.last_mut().unwrap();
+                if !scope.nodes.iter().any(|n| n == name) {
+                    scope.nodes.push(name.to_string());
+                }
+                if !scope.subgraph.nodes.iter().any(|n| n == name) {
+                    scope.subgraph.nodes.push(name.to_string());
+                }
+                if !self.graph.contains_node(name) {
+                    self.node_defaults
+                        .insert(name.to_string(), scope.node_attrs.clone());
+                }
+                let idx = self.graph.insert_node(name);
+                self.graph.nodes.node_mut(idx)
+            }
+
+            fn subgraph(&mut self) -> Result<Vec<(String, Option<String>)>, ParseError> {
+                let mut name = None;
+                if self.eat(&Token::Keyword(Keyword::Subgraph)) && self.is_id() {
+                    name = Some(self.id()?);
+                }
+                self.expect(Token::LeftBrace, "`{`")?;
+                let parent = self.scopes.last().unwrap();
+                self.scopes.push(Scope {
+                    node_attrs: parent.node_attrs.clone(),
+                    edge_attrs: parent.edge_attrs.clone(),
+                    nodes: Vec::new(),
+                    subgraph: Subgraph {
+                        name,
+                        ..Subgraph::default()
+                    },
+                });
+                self.statements()?;
+                self.expect(Token::RightBrace, "`}`")?;
+                let Scope {
+                    nodes, subgraph, ..
+                } = self.scopes.pop().unwrap();
+                let parent = self.scope();
+                for name in &nodes {
+                    if !parent.nodes.contains(name) {
+                        parent.nodes.push(name.clone());
+                    }
+                }
+                let siblings = &mut parent.subgraph;
+                let existing = subgraph.name.as_ref().and_then(|name| {
+                    siblings
+                        .subgraphs
+                        .iter_mut()
+                        .find(|sub| sub.name.as_ref() == Some(name))
+                });
+                match existing {
+                    // A subgraph named again is the same one.
+                    Some(sub) => merge(sub, subgraph),
+                    None if subgraph.name.is_none()
+                        && subgraph.attrs.is_empty()
+                        && subgraph.node_attrs.is_empty()
+                        && subgraph.edge_attrs.is_empty() =>
+                    {
+                        merge(siblings, subgraph)
+                    }
+                    None => siblings.subgraphs.push(subgraph),
+                }
+                Ok(nodes.into_iter().map(|name| (name, None)).collect())
+            }
+
+            // The rest of an edge statement, `tail` being the nodes it starts from. A chain
+            // such as `a -> b -> c` makes an edge out of every consecutive pair, and a
+            // subgraph at either end connects all of its nodes.
+            fn edges(&mut self, tail: Vec<(String, Option<String>)>) -> Result<(), ParseError> {
+                let mut ends = vec![tail];
+                while self.is_edge_op() {
+                    let (op, expected) = match self.graph.kind {
+                        GraphKind::Undirected => (Token::UndirectedEdge, "`--`"),
+                        GraphKind::Directed => (Token::DirectedEdge, "`->`"),
+                    };
+                    if !self.eat(&op) {
+                        return Err(self.error(expected));
+                    }
+                    let end = match self.peek() {
+                        Some(Token::Keyword(Keyword::Subgraph) | Token::LeftBrace) => {
+                            self.subgraph()?
+                        }
+                        _ => vec![self.node_id()?],
+                    };
+                    ends.push(end);
+                }
+                let attrs = self.attr_lists()?;
+                for pair in ends.windows(2) {
+                    for (from, tail_port) in &pair[0] {
+                        for (to, head_port) in &pair[1] {
+                            let mut edge_attrs = attrs.clone();
+                            if let Some(port) = tail_port {
+                                edge_attrs.insert("tailport".to_string(), port.clone());
+                            }
+                            if let Some(port) = head_port {
+                                edge_attrs.insert("headport".to_string(), port.clone());
+                            }
+                            self.edge(from, to, edge_attrs);
+                        }
+                    }
+                }
+                Ok(())
+            }
+
+            fn edge(&mut self, from: &str, to: &str, attrs: HashMap<String, String>) {
+                let mut edge = Edge::new(from, to);
+                edge.attrs = attrs;
+                let count = self.graph.edges.len();
+                self.graph.push_edge(edge);
+                // An edge merged into an existing one of a strict graph keeps its defaults.
+                if self.graph.edges.len() > count {
+                    let defaults = self.scope().edge_attrs.clone();
+                    self.edge_defaults.push(defaults);
+                }
+            }
+        }
+
+        // Moves the nodes, attributes and subgraphs of `from` into `into`.
+        fn merge(into: &mut Subgraph, from: Subgraph) {
+            for name in from.nodes {
+                if !into.nodes.contains(&name) {
+                    into.nodes.push(name);
+                }
+            }
+            into.attrs.extend(from.attrs);
+            into.node_attrs.extend(from.node_attrs);
+            into.edge_attrs.extend(from.edge_attrs);
+            into.subgraphs.extend(from.subgraphs);
+        }
+    }
+
+    pub mod algorithms {
+        use super::{Graph, GraphKind};
+        use std::cmp::Ordering;
+        use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+
+        #[derive(Debug, PartialEq, Clone)]
+        pub enum AlgorithmError {
+            UnknownNode(String),
+            // Topological order only exists for directed graphs.
+            NotDirected,
+            // A cycle preventing a topological order, its first node repeated at the end.
+            Cycle(Vec<String>),
+            InvalidWeight {
+                from: String,
+                to: String,
+                value: String,
+            },
+        }
+
+        #[derive(Debug, PartialEq, Clone)]
+        pub struct Path {
+            pub cost: f64,
+            pub nodes: Vec<String>,
+        }
+
+        // The nodes of the graph, including the ones only named by edges, with the edges
+        // leaving and entering each of them. Undirected edges are followed both ways.
+        struct Adjacency<'a> {
+            names: Vec<&'a str>,
+            index: HashMap<&'a str, usize>,
+            outgoing: Vec<Vec<(usize, usize)>>,
+            incoming: Vec<Vec<(usize, usize)>>,
+        }
+
+        impl<'a> Adjacency<'a> {
+            fn new(graph: &'a Graph) -> Self {
+                let mut names = Vec::new();
+                let mut index = HashMap::new();
+                let endpoints = graph
+                    .edges
+                    .iter()
+                    .flat_map(|edge| [edge.from.as_str(), edge.to.as_str()]);
+                for name in graph.nodes.iter().map(|n| n.name.as_str()).chain(endpoints) {
+                    index.entry(name).or_insert_with(|| {
+                        names.push(name);
+                        names.len() - 1
+                    });
+                }
+                let mut outgoing = vec![Vec::new(); names.len()];
+                let mut incoming = vec![Vec::new(); names.len()];
+                for (edge_idx, edge) in graph.edges.iter().enumerate() {
+                    let (from, to) = (index[edge.from.as_str()], index[edge.to.as_str()]);
+                    outgoing[from].push((to, edge_idx));
+                    incoming[to].push((from, edge_idx));
+                    if graph.kind == GraphKind::Undirected && from != to {
+                        outgoing[to].push((from, edge_idx));
+                        incoming[from].push((to, edge_idx));
+                    }
+                }
+                Self {
+                    names,
+                    index,
+                    outgoing,
+                    incoming,
+                }
+            }
+
+            fn lookup(&self, name: &str) -> Result<usize, AlgorithmError> {
+                self.index
+                    .get(name)
+                    .copied()
+                    .ok_or_else(|| AlgorithmError::UnknownNode(name.to_string()))
+            }
+
+            fn neighbours(&self, idx: usize, edges: &[Vec<(usize, usize)>]) -> Vec<usize> {
+                let mut seen = HashSet::new();
+                edges[idx]
+                    .iter()
+                    .map(|(other, _)| *other)
+                    .filter(|other| seen.insert(*other))
+                    .collect()
+            }
+
+            fn names(&self, nodes: &[usize]) -> Vec<&'a str> {
+                nodes.iter().map(|idx| self.names[*idx]).collect()
+            }
+
+            // Nodes reachable from `start` in the order they are visited, either breadth
+            // or depth first.
+            fn traverse(&self, start: usize, breadth_first: bool) -> Vec<usize> {
+                let mut visited = vec![false; self.names.len()];
+                let mut order = Vec::new();
+                let mut pending = VecDeque::from([start]);
+                while let Some(idx) = match breadth_first {
+                    true => pending.pop_front(),
+                    false => pending.pop_back(),
+                } {
+                    if visited[idx] {
+                        continue;
+                    }
+                    visited[idx] = true;
+                    order.push(idx);
+                    let mut next = self.neighbours(idx, &self.outgoing);
+                    // Depth first visits the first successor first, so it goes on top.
+                    if !breadth_first {
+                        next.reverse();
+                    }
+                    pending.extend(next.into_iter().filter(|n| !visited[*n]));
+                }
+                order
+            }
+
+            // Groups of nodes connected to one another through `edges`, in the order their
+            // first node comes in `starts`.
+            fn components(
+                &self,
+                edges: &[Vec<(usize, usize)>],
+                starts: impl IntoIterator<Item = usize>,
+            ) -> Vec<Vec<usize>> {
+                let mut component = vec![usize::MAX; self.names.len()];
+                let mut components: Vec<Vec<usize>> = Vec::new();
+                for start in starts {
+                    if component[start] != usize::MAX {
+                        continue;
+                    }
+                    let mut stack = vec![start];
+                    component[start] = components.len();
+                    let mut members = Vec::new();
+                    while let Some(idx) = stack.pop() {
+                        members.push(idx);
+                        for (other, _) in &edges[idx] {
+                            if component[*other] == usize::MAX {
+                                component[*other] = components.len();
+                                stack.push(*other);
+                            }
+                        }
+                    }
+                    members.sort_unstable();
+                    components.push(members);
+                }
+                components
+            }
+        }
+
+        // Entry of the queue of the shortest path search, the cheapest coming first.
+        struct Candidate {
+            cost: f64,
+            node: usize,
+        }
+
+        impl PartialEq for Candidate {
+            fn eq(&self, other: &Self) -> bool {
+                self.cmp(other) == Ordering::Equal
+            }
+        }
+
+        impl Eq for Candidate {}
+
+        impl PartialOrd for Candidate {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for Candidate {
+            fn cmp(&self, other: &Self) -> Ordering {
+                other
+                    .cost
+                    .total_cmp(&self.cost)
+                    .then_with(|| other.node.cmp(&self.node))
+            }
+        }
+
+        impl Graph {
+            // Nodes reached by an edge leaving `name`, or any edge touching it when the
+            // graph is undirected.
+            pub fn successors(&self, name: &str) -> Option<Vec<&str>> {
+                let adjacency = Adjacency::new(self);
+                let idx = adjacency.lookup(name).ok()?;
+                Some(adjacency.names(&adjacency.neighbours(idx, &adjacency.outgoing)))
+            }
+
+            pub fn predecessors(&self, name: &str) -> Option<Vec<&str>> {
+                let adjacency = Adjacency::new(self);
+                let idx = adjacency.lookup(name).ok()?;
+                Some(adjacency.names(&adjacency.neighbours(idx, &adjacency.incoming)))
+            }
+
+            pub fn out_degree(&self, name: &str) -> Option<usize> {
+                let adjacency = Adjacency::new(self);
+                let idx = adjacency.lookup(name).ok()?;
+                Some(adjacency.outgoing[idx].len())
+            }
+
+            pub fn in_degree(&self, name: &str) -> Option<usize> {
+                let adjacency = Adjacency::new(self);
+                let idx = adjacency.lookup(name).ok()?;
+                Some(adjacency.incoming[idx].len())
+            }
+
+            // Number of edge ends at the node, a loop counting twice.
+            pub fn degree(&self, name: &str) -> Option<usize> {
+                let adjacency = Adjacency::new(self);
+                let idx = adjacency.lookup(name).ok()?;
+                Some(
+                    self.edges
+                        .iter()
+                        .map(|edge| {
+                            usize::from(adjacency.index[edge.from.as_str()] == idx)
+                                + usize::from(adjacency.index[edge.to.as_str()] == idx)
+                        })
+                        .sum(),
+                )
+            }
+
+            pub fn bfs(&self, start: &str) -> Option<Vec<&str>> {
+                let adjacency = Adjacency::new(self);
+                let idx = adjacency.lookup(start).ok()?;
+                Some(adjacency.names(&adjacency.traverse(idx, true)))
+            }
+
+            pub fn dfs(&self, start: &str) -> Option<Vec<&str>> {
+                let adjacency = Adjacency::new(self);
+                let idx = adjacency.lookup(start).ok()?;
+                Some(adjacency.names(&adjacency.traverse(idx, false)))
+            }
+
+            // Orders the nodes so that every edge goes from an earlier node to a later one,
+            // ties being broken by the order of the nodes in the graph.
+            pub fn topological_sort(&self) -> Result<Vec<&str>, AlgorithmError> {
+                if self.kind != GraphKind::Directed {
+                    return Err(AlgorithmError::NotDirected);
+                }
+                let adjacency = Adjacency::new(self);
+                let mut in_degree: Vec<usize> =
+                    adjacency.incoming.iter().map(|edges| edges.len()).collect();
+                let mut ready: Vec<usize> = (0..adjacency.names.len())
+                    .filter(|idx| in_degree[*idx] == 0)
+                    .rev()
+                    .collect();
+                let mut order = Vec::new();
+                while let Some(idx) = ready.pop() {
+                    order.push(idx);
+                    for (next, _) in &adjacency.outgoing[idx] {
+                        in_degree[*next] -= 1;
+                        if in_degree[*next] == 0 {
+                            // Keep the ready nodes sorted, the first one being on top.
+                            let position = ready.partition_point(|other| other > next);
+                            ready.insert(position, *next);
+                        }
+                    }
+                }
+                if order.len() == adjacency.names.len() {
+                    return Ok(adjacency.names(&order));
+                }
+                Err(AlgorithmError::Cycle(
+                    find_cycle(&adjacency, &in_degree)
+                        .into_iter()
+                        .map(|idx| adjacency.names[idx].to_string())
+                        .collect(),
+                ))
+            }
+
+            // Groups of nodes that can all reach one another following the edges, found
+            // with Kosaraju's algorithm.
+            pub fn strongly_connected_components(&self) -> Vec<Vec<&str>> {
+                let adjacency = Adjacency::new(self);
+                let count = adjacency.names.len();
+                // Nodes in the order a depth-first search along the edges is done with them.
+                let mut visited = vec![false; count];
+                let mut finished = Vec::with_capacity(count);
+                for start in 0..count {
+                    if visited[start] {
+                        continue;
+                    }
+                    visited[start] = true;
+                    let mut stack = vec![(start, 0)];
+                    while let Some((idx, next)) = stack.last().copied() {
+                        match adjacency.outgoing[idx].get(next) {
+                            Some(&(other, _)) => {
+                                stack.last_mut().unwrap().1 += 1;
+                                if !visited[other] {
+                                    visited[other] = true;
+                                    stack.push((other, 0));
+                                }
+                            }
+                            None => {
+                                finished.push(idx);
+                                stack.pop();
+                            }
+                        }
+                    }
+                }
+                // Going against the edges from the last finished node first, each search
+                // stays within one component.
+                let mut components =
+                    adjacency.components(&adjacency.incoming, finished.into_iter().rev());
+                components.sort_unstable_by_key(|component| component[0]);
+                components
+                    .iter()
+                    .map(|component| adjacency.names(component))
+                    .collect()
+            }
+
+            // Groups of nodes connected to one another, ignoring the direction of the edges.
+            pub fn weakly_connected_components(&self) -> Vec<Vec<&str>> {
+                let adjacency = Adjacency::new(self);
+                let both_ways: Vec<Vec<(usize, usize)>> = adjacency
+                    .outgoing
+                    .iter()
+                    .zip(&adjacency.incoming)
+                    .map(|(outgoing, incoming)| outgoing.iter().chain(incoming).copied().collect())
+                    .collect();
+                adjacency
+                    .components(&both_ways, 0..adjacency.names.len())
+                    .iter()
+                    .map(|component| adjacency.names(component))
+                    .collect()
+            }
+
+            // Cheapest path between two nodes, the cost of an edge being the number in its
+            // `weight_attr` attribute, inherited from the `edge` defaults if need be, or 1
+            // when it has none. Returns `None` when `to` cannot be reached from `from`.
+            pub fn shortest_path(
+                &self,
+                from: &str,
+                to: &str,
+                weight_attr: &str,
+            ) -> Result<Option<Path>, AlgorithmError> {
+                let adjacency = Adjacency::new(self);
+                let (start, target) = (adjacency.lookup(from)?, adjacency.lookup(to)?);
+                let weights = self
+                    .edges
+                    .iter()
+                    .map(|edge| match self.edge_attr(edge, weight_attr) {
+                        None => Ok(1.0),
+                        Some(value) => value
+                            .parse::<f64>()
+                            .ok()
+                            .filter(|weight| *weight >= 0.0)
+                            .ok_or_else(|| AlgorithmError::InvalidWeight {
+                                from: edge.from.clone(),
+                                to: edge.to.clone(),
+                                value: value.to_string(),
+                            }),
+                    })
+                    .collect::<Result<Vec<f64>, _>>()?;
+                let mut cost = vec![f64::INFINITY; adjacency.names.len()];
+                let mut previous = vec![None; adjacency.names.len()];
+                let mut queue = BinaryHeap::from([Candidate {
+                    cost: 0.0,
+                    node: start,
+                }]);
+                cost[start] = 0.0;
+                while let Some(Candidate {
+                    cost: reached,
+                    node,
+                }) = queue.pop()
+                {
+                    if node == target {
+                        let mut nodes = vec![node];
+                        while let Some(prev) = previous[*nodes.last().unwrap()] {
+                            nodes.push(prev);
+                        }
+                        nodes.reverse();
+                        return Ok(Some(Path {
+                            cost: reached,
+                            nodes: nodes
+                                .into_iter()
+                                .map(|idx| adjacency.names[idx].to_string())
+                                .collect(),
+                        }));
+                    }
+                    if reached > cost[node] {
+                        continue;
+                    }
+                    for (next, edge_idx) in &adjacency.outgoing[node] {
+                        let through = reached + weights[*edge_idx];
+                        if through < cost[*next] {
+                            cost[*next] = through;
+                            previous[*next] = Some(node);
+                            queue.push(Candidate {
+                                cost: through,
+                                node: *next,
+                            });
+                        }
+                    }
+                }
+                Ok(None)
+            }
+        }
+
+        // A cycle among the nodes left over by the topological sort, all of which have an
+        // incoming edge from another left over node. Walking back along those edges must
+        // eventually come back to a node already seen.
+        fn find_cycle(adjacency: &Adjacency, in_degree: &[usize]) -> Vec<usize> {
+            let start = (0..in_degree.len())
+                .find(|idx| in_degree[*idx] > 0)
+                .unwrap();
+            let mut walk = vec![start];
+            loop {
+                let current = *walk.last().unwrap();
+                let (prev, _) = adjacency.incoming[current]
+                    .iter()
+                    .find(|(prev, _)| in_degree[*prev] > 0)
+                    .unwrap();
+                if let Some(position) = walk.iter().position(|idx| idx == prev) {
+                    let mut cycle = walk[position..].to_vec();
+                    cycle.reverse();
+                    // Start from the earliest node so the same cycle is always reported alike.
+                    let first = (0..cycle.len()).min_by_key(|i| cycle[*i]).unwrap();
+                    cycle.rotate_left(first);
+                    cycle.push(cycle[0]);
+                    return cycle;
+                }
+                walk.push(*prev);
+            }
+        }
+    }
+
+    // Typed access to the standard Graphviz attributes. The attributes are still stored
+    // as strings, so unknown ones remain available through `attrs` and `attr`, while the
+    // typed getters and setters check the values of the known ones.
+    pub mod attributes {
+        use super::graph_items::edge::Edge;
+        use super::graph_items::node::Node;
+        use super::graph_items::subgraph::Subgraph;
+        use super::Graph;
+        use std::collections::HashMap;
+        use std::fmt;
+        use std::str::FromStr;
+
+        #[derive(Debug, PartialEq, Eq, Clone)]
+        pub enum AttrError {
+            // The attribute does not exist for this kind of item.
+            UnknownAttribute(String),
+            InvalidValue { key: String, value: String },
+        }
+
+        // Enumerations written as a fixed set of keywords in DOT.
+        macro_rules! keywords {
+            ($(#[$meta:meta])* $name:ident { $($variant:ident => $keyword:literal),+ $(,)? }) => {
+                $(#[$meta])*
+                #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+                pub enum $name {
+                    $($variant),+
+                }
+
+                impl $name {
+                    fn keyword(&self) -> &'static str {
+                        match self {
+                            $($name::$variant => $keyword),+
+                        }
+                    }
+                }
+
+                impl FromStr for $name {
+                    type Err = ();
+
+                    fn from_str(s: &str) -> Result<Self, Self::Err> {
+                        match s {
+                            $($keyword => Ok($name::$variant),)+
+                            _ => Err(()),
+                        }
+                    }
+                }
+
+                impl fmt::Display for $name {
+                    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                        write!(f, "{}", self.keyword())
+                    }
+                }
+            };
+        }
+
+        keywords! {
+            Shape {
+                Box => "box",
+                Polygon => "polygon",
+                Ellipse => "ellipse",
+                Oval => "oval",
+                Circle => "circle",
+                Point => "point",
+                Egg => "egg",
+                Triangle => "triangle",
+                Plaintext => "plaintext",
+                Plain => "plain",
+                Diamond => "diamond",
+                Trapezium => "trapezium",
+                Parallelogram => "parallelogram",
+                House => "house",
+                Pentagon => "pentagon",
+                Hexagon => "hexagon",
+                Septagon => "septagon",
+                Octagon => "octagon",
+                DoubleCircle => "doublecircle",
+                DoubleOctagon => "doubleoctagon",
+                TripleOctagon => "tripleoctagon",
+                InvTriangle => "invtriangle",
+                InvTrapezium => "invtrapezium",
+                InvHouse => "invhouse",
+                MDiamond => "Mdiamond",
+                MSquare => "Msquare",
+                MCircle => "Mcircle",
+                Rect => "rect",
+                Rectangle => "rectangle",
+                Square => "square",
+                Star => "star",
+                None => "none",
+                Underline => "underline",
+                Cylinder => "cylinder",
+                Note => "note",
+                Tab => "tab",
+                Folder => "folder",
+                Box3d => "box3d",
+                Component => "component",
+                Record => "record",
+                MRecord => "Mrecord",
+            }
+        }
+
+        keywords! {
+            Style {
+                Solid => "solid",
+                Dashed => "dashed",
+                Dotted => "dotted",
+                Bold => "bold",
+                Invisible => "invis",
+                Filled => "filled",
+                Rounded => "rounded",
+                Diagonals => "diagonals",
+                Striped => "striped",
+                Wedged => "wedged",
+                Radial => "radial",
+                Tapered => "tapered",
+            }
+        }
+
+        keywords! {
+            RankDir {
+                TopToBottom => "TB",
+                LeftToRight => "LR",
+                BottomToTop => "BT",
+                RightToLeft => "RL",
+            }
+        }
+
+        impl Style {
+            // Styles only make sense for some items, e.g. `tapered` for edges.
+            fn applies_to(&self, target: Target) -> bool {
+                match self {
+                    Style::Solid | Style::Dashed | Style::Dotted | Style::Bold => true,
+                    Style::Invisible => target != Target::Graph,
+                    Style::Tapered => target == Target::Edge,
+                    Style::Wedged | Style::Diagonals => target == Target::Node,
+                    Style::Filled | Style::Rounded | Style::Striped | Style::Radial => {
+                        target != Target::Edge
+                    }
+                }
+            }
+        }
+
+        // A color written by name, as `#rrggbb`, `#rrggbbaa` or as hue, saturation and
+        // value between 0 and 1.
+        #[derive(Debug, PartialEq, Clone)]
+        pub enum Color {
+            Named(String),
+            Rgb(u8, u8, u8),
+            Rgba(u8, u8, u8, u8),
+            Hsv(f64, f64, f64),
+        }
+
+        // The colors of the X11 scheme of Graphviz, which also knows the SVG names that
+        // X11 lacks.
+        const COLOR_NAMES: [&str; 162] = [
+            "aliceblue",
+            "antiquewhite",
+            "aqua",
+            "aquamarine",
+            "azure",
+            "beige",
+            "bisque",
+            "black",
+            "blanchedalmond",
+            "blue",
+            "blueviolet",
+            "brown",
+            "burlywood",
+            "cadetblue",
+            "chartreuse",
+            "chocolate",
+            "coral",
+            "cornflowerblue",
+            "cornsilk",
+            "crimson",
+            "cyan",
+            "darkblue",
+            "darkcyan",
+            "darkgoldenrod",
+            "darkgray",
+            "darkgreen",
+            "darkgrey",
+            "darkkhaki",
+            "darkmagenta",
+            "darkolivegreen",
+            "darkorange",
+            "darkorchid",
+            "darkred",
+            "darksalmon",
+            "darkseagreen",
+            "darkslateblue",
+            "darkslategray",
+            "darkslategrey",
+            "darkturquoise",
+            "darkviolet",
+            "deeppink",
+            "deepskyblue",
+            "dimgray",
+            "dimgrey",
+            "dodgerblue",
+            "firebrick",
+            "floralwhite",
+            "forestgreen",
+            "fuchsia",
+            "gainsboro",
+            "ghostwhite",
+            "gold",
+            "goldenrod",
+            "gray",
+            "green",
+            "greenyellow",
+            "grey",
+            "honeydew",
+            "hotpink",
+            "indianred",
+            "indigo",
+            "ivory",
+            "khaki",
+            "lavender",
+            "lavenderblush",
+            "lawngreen",
+            "lemonchiffon",
+            "lightblue",
+            "lightcoral",
+            "lightcyan",
+            "lightgoldenrod",
+            "lightgoldenrodyellow",
+            "lightgray",
+            "lightgreen",
+            "lightgrey",
+            "lightpink",
+            "lightsalmon",
+            "lightseagreen",
+            "lightskyblue",
+            "lightslateblue",
+            "lightslategray",
+            "lightslategrey",
+            "lightsteelblue",
+            "lightyellow",
+            "lime",
+            "limegreen",
+            "linen",
+            "magenta",
+            "maroon",
+            "mediumaquamarine",
+            "mediumblue",
+            "mediumorchid",
+            "mediumpurple",
+            "mediumseagreen",
+            "mediumslateblue",
+            "mediumspringgreen",
+            "mediumturquoise",
+            "mediumvioletred",
+            "midnightblue",
+            "mintcream",
+            "mistyrose",
+            "moccasin",
+            "navajowhite",
+            "navy",
+            "navyblue",
+            "oldlace",
+            "olive",
+            "olivedrab",
+            "orange",
+            "orangered",
+            "orchid",
+            "palegoldenrod",
+            "palegreen",
+            "paleturquoise",
+            "palevioletred",
+            "papayawhip",
+            "peachpuff",
+            "peru",
+            "pink",
+            "plum",
+            "powderblue",
+            "purple",
+            "rebeccapurple",
+            "red",
+            "rosybrown",
+            "royalblue",
+            "saddlebrown",
+            "salmon",
+            "sandybrown",
+            "seagreen",
+            "seashell",
+            "sienna",
+            "silver",
+            "skyblue",
+            "slateblue",
+            "slategray",
+            "slategrey",
+            "snow",
+            "springgreen",
+            "steelblue",
+            "tan",
+            "teal",
+            "thistle",
+            "tomato",
+            "turquoise",
+            "violet",
+            "violetred",
+            "webgray",
+            "webgreen",
+            "webgrey",
+            "webmaroon",
+            "webpurple",
+            "wheat",
+            "white",
+            "whitesmoke",
+            "x11gray",
+            "x11green",
+            "x11grey",
+            "x11maroon",
+            "x11purple",
+            "yellow",
+            "yellowgreen",
+        ];
+
+        // Names standing for no color at all, which have no shades.
+        const NO_COLOR: [&str; 3] = ["transparent", "none", "invis"];
+
+        fn is_color_name(name: &str) -> bool {
+            let name = name.to_ascii_lowercase();
+            // X11 has shades numbered 1 to 4 of most colors, and grays from 0 to 100.
+            let base = name.trim_end_matches(|c: char| c.is_ascii_digit());
+            let shade = &name[base.len()..];
+            match (base, shade.parse::<u8>()) {
+                (_, _) if shade.is_empty() => {
+                    COLOR_NAMES.contains(&base) || NO_COLOR.contains(&base)
+                }
+                ("gray" | "grey", Ok(level)) => level <= 100 && shade.len() <= 3,
+                (_, Ok(1..=4)) => shade.len() == 1 && COLOR_NAMES.contains(&base),
+                _ => false,
+            }
+        }
+
+        impl FromStr for Color {
+            type Err = ();
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                if let Some(hex) = s.strip_prefix('#') {
+                    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                        return Err(());
+                    }
+                    let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| ());
+                    return match hex.len() {
+                        6 => Ok(Color::Rgb(byte(0)?, byte(2)?, byte(4)?)),
+                        8 => Ok(Color::Rgba(byte(0)?, byte(2)?, byte(4)?, byte(6)?)),
+                        _ => Err(()),
+                    };
+                }
+                let parts: Vec<&str> = s
+                    .split(|c: char| c == ',' || c.is_whitespace())
+                    .filter(|part| !part.is_empty())
+                    .collect();
+                if parts.len() == 3 {
+                    let values = parts
+                        .iter()
+                        .map(|part| part.parse::<f64>().ok().filter(|v| (0.0..=1.0).contains(v)))
+                        .collect::<Option<Vec<f64>>>()
+                        .ok_or(())?;
+                    return Ok(Color::Hsv(values[0], values[1], values[2]));
+                }
+                // Names may be qualified by a color scheme, e.g. `/accent3/1`.
+                let scheme_name = s
+                    .strip_prefix('/')
+                    .and_then(|rest| rest.split_once('/'))
+                    .is_some_and(|(scheme, name)| !scheme.is_empty() && !name.is_empty());
+                match scheme_name || is_color_name(s) {
+                    true => Ok(Color::Named(s.to_string())),
+                    false => Err(()),
+                }
+            }
+        }
+
+        impl fmt::Display for Color {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                    Color::Named(name) => write!(f, "{}", name),
+                    Color::Rgb(r, g, b) => write!(f, "#{:02x}{:02x}{:02x}", r, g, b),
+                    Color::Rgba(r, g, b, a) => write!(f, "#{:02x}{:02x}{:02x}{:02x}", r, g, b, a),
+                    Color::Hsv(h, s, v) => write!(f, "{} {} {}", h, s, v),
+                }
+            }
+        }
+
+        // The value of the color attributes: one or more colors separated by `:`, each
+        // with an optional fraction of the area to fill, as in `red;0.3:blue`.
+        #[derive(Debug, PartialEq, Clone)]
+        pub struct ColorList(pub Vec<(Color, Option<f64>)>);
+
+        impl ColorList {
+            // The first color, which is the only one for most items.
+            pub fn first(&self) -> Option<&Color> {
+                self.0.first().map(|(color, _)| color)
+            }
+        }
+
+        impl From<Color> for ColorList {
+            fn from(color: Color) -> Self {
+                ColorList(vec![(color, None)])
+            }
+        }
+
+        impl FromStr for ColorList {
+            type Err = ();
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let colors = s
+                    .split(':')
+                    .map(|color| {
+                        let Some((color, fraction)) = color.split_once(';') else {
+                            return Ok((color.parse()?, None));
+                        };
+                        let fraction = fraction.parse::<f64>().map_err(|_| ())?;
+                        match (0.0..=1.0).contains(&fraction) {
+                            true => Ok((color.parse()?, Some(fraction))),
+                            false => Err(()),
+                        }
+                    })
+                    .collect::<Result<Vec<_>, ()>>()?;
+                Ok(ColorList(colors))
+            }
+        }
+
+        impl fmt::Display for ColorList {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                for (idx, (color, fraction)) in self.0.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, ":")?;
+                    }
+                    write!(f, "{}", color)?;
+                    if let Some(fraction) = fraction {
+                        write!(f, ";{}", fraction)?;
+                    }
+                }
+                Ok(())
+            }
+        }
+
+        #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+        enum Target {
+            Graph,
+            Subgraph,
+            Node,
+            Edge,
+        }
+
+        impl Target {
+            // Letters of the "Used By" column of the Graphviz documentation.
+            fn letters(&self) -> &'static [char] {
+                match self {
+                    Target::Graph => &['G'],
+                    Target::Subgraph => &['G', 'S', 'C'],
+                    Target::Node => &['N'],
+                    Target::Edge => &['E'],
+                }
+            }
+        }
+
+        #[derive(Clone, Copy)]
+        enum Kind {
+            Text,
+            // One or more colors separated by `:`, each with an optional `;fraction`.
+            Colors,
+            Shape,
+            Styles,
+            Number(f64),
+            Integer(i64),
+            Bool,
+            OneOf(&'static [&'static str]),
+        }
+
+        const ATTRIBUTES: [(&str, &str, Kind); 176] = [
+            ("_background", "G", Kind::Text),
+            ("area", "NC", Kind::Number(0.0)),
+            ("arrowhead", "E", Kind::Text),
+            ("arrowsize", "E", Kind::Number(0.0)),
+            ("arrowtail", "E", Kind::Text),
+            ("bb", "GC", Kind::Text),
+            ("beautify", "G", Kind::Bool),
+            ("bgcolor", "GC", Kind::Colors),
+            ("center", "G", Kind::Bool),
+            ("charset", "G", Kind::Text),
+            ("class", "ENCG", Kind::Text),
+            ("cluster", "CGS", Kind::Bool),
+            (
+                "clusterrank",
+                "G",
+                Kind::OneOf(&["local", "global", "none"]),
+            ),
+            ("color", "ENC", Kind::Colors),
+            ("colorscheme", "ENCG", Kind::Text),
+            ("comment", "ENG", Kind::Text),
+            ("compound", "G", Kind::Bool),
+            ("concentrate", "G", Kind::Bool),
+            ("constraint", "E", Kind::Bool),
+            ("Damping", "G", Kind::Number(0.0)),
+            ("decorate", "E", Kind::Bool),
+            ("defaultdist", "G", Kind::Number(f64::MIN)),
+            ("dim", "G", Kind::Integer(2)),
+            ("dimen", "G", Kind::Integer(2)),
+            (
+                "dir",
+                "E",
+                Kind::OneOf(&["forward", "back", "both", "none"]),
+            ),
+            ("diredgeconstraints", "G", Kind::Text),
+            ("distortion", "N", Kind::Number(-100.0)),
+            ("dpi", "G", Kind::Number(0.0)),
+            ("edgehref", "E", Kind::Text),
+            ("edgetarget", "E", Kind::Text),
+            ("edgetooltip", "E", Kind::Text),
+            ("edgeURL", "E", Kind::Text),
+            ("epsilon", "G", Kind::Number(f64::MIN)),
+            ("esep", "G", Kind::Text),
+            ("fillcolor", "NEC", Kind::Colors),
+            ("fixedsize", "N", Kind::OneOf(&["true", "false", "shape"])),
+            ("fontcolor", "ENGC", Kind::Colors),
+            ("fontname", "ENGC", Kind::Text),
+            ("fontnames", "G", Kind::Text),
+            ("fontpath", "G", Kind::Text),
+            ("fontsize", "ENGC", Kind::Number(1.0)),
+            ("forcelabels", "G", Kind::Bool),
+            ("gradientangle", "NCG", Kind::Integer(i64::MIN)),
+            ("group", "N", Kind::Text),
+            ("head_lp", "E", Kind::Text),
+            ("headclip", "E", Kind::Bool),
+            ("headhref", "E", Kind::Text),
+            ("headlabel", "E", Kind::Text),
+            ("headport", "E", Kind::Text),
+            ("headtarget", "E", Kind::Text),
+            ("headtooltip", "E", Kind::Text),
+            ("headURL", "E", Kind::Text),
+            ("height", "N", Kind::Number(0.02)),
+            ("href", "GCNE", Kind::Text),
+            ("id", "GCNE", Kind::Text),
+            ("image", "N", Kind::Text),
+            ("imagepath", "G", Kind::Text),
+            (
+                "imagepos",
+                "N",
+                Kind::OneOf(&["tl", "tc", "tr", "ml", "mc", "mr", "bl", "bc", "br"]),
+            ),
+            (
+                "imagescale",
+                "N",
+                Kind::OneOf(&["false", "true", "width", "height", "both"]),
+            ),
+            ("inputscale", "G", Kind::Number(f64::MIN)),
+            ("K", "GC", Kind::Number(0.0)),
+            ("label", "ENGC", Kind::Text),
+            ("label_scheme", "G", Kind::Integer(0)),
+            ("labelangle", "E", Kind::Number(-180.0)),
+            ("labeldistance", "E", Kind::Number(0.0)),
+            ("labelfloat", "E", Kind::Bool),
+            ("labelfontcolor", "E", Kind::Colors),
+            ("labelfontname", "E", Kind::Text),
+            ("labelfontsize", "E", Kind::Number(1.0)),
+            ("labelhref", "E", Kind::Text),
+            ("labeljust", "GC", Kind::OneOf(&["l", "r", "c"])),
+            ("labelloc", "NGC", Kind::OneOf(&["t", "c", "b"])),
+            ("labeltarget", "E", Kind::Text),
+            ("labeltooltip", "E", Kind::Text),
+            ("labelURL", "E", Kind::Text),
+            ("landscape", "G", Kind::Bool),
+            ("layer", "ENC", Kind::Text),
+            ("layerlistsep", "G", Kind::Text),
+            ("layers", "G", Kind::Text),
+            ("layerselect", "G", Kind::Text),
+            ("layersep", "G", Kind::Text),
+            ("layout", "G", Kind::Text),
+            ("len", "E", Kind::Number(f64::MIN)),
+            ("levels", "G", Kind::Integer(0)),
+            ("levelsgap", "G", Kind::Number(f64::MIN)),
+            ("lhead", "E", Kind::Text),
+            ("lheight", "GC", Kind::Number(f64::MIN)),
+            ("linelength", "G", Kind::Integer(0)),
+            ("lp", "EGC", Kind::Text),
+            ("ltail", "E", Kind::Text),
+            ("lwidth", "GC", Kind::Number(f64::MIN)),
+            ("margin", "NCG", Kind::Text),
+            ("maxiter", "G", Kind::Integer(0)),
+            ("mclimit", "G", Kind::Number(f64::MIN)),
+            ("mindist", "G", Kind::Number(0.0)),
+            ("minlen", "E", Kind::Integer(0)),
+            ("mode", "G", Kind::Text),
+            ("model", "G", Kind::Text),
+            ("newrank", "G", Kind::Bool),
+            ("nodesep", "G", Kind::Number(0.02)),
+            ("nojustify", "GCNE", Kind::Bool),
+            ("normalize", "G", Kind::Text),
+            ("notranslate", "G", Kind::Bool),
+            ("nslimit", "G", Kind::Number(f64::MIN)),
+            ("nslimit1", "G", Kind::Number(f64::MIN)),
+            ("oneblock", "G", Kind::Bool),
+            ("ordering", "GN", Kind::OneOf(&["in", "out", ""])),
+            ("orientation", "NG", Kind::Text),
+            (
+                "outputorder",
+                "G",
+                Kind::OneOf(&["breadthfirst", "nodesfirst", "edgesfirst"]),
+            ),
+            ("overlap", "G", Kind::Text),
+            ("overlap_scaling", "G", Kind::Number(f64::MIN)),
+            ("overlap_shrink", "G", Kind::Bool),
+            ("pack", "G", Kind::Text),
+            ("packmode", "G", Kind::Text),
+            ("pad", "G", Kind::Text),
+            ("page", "G", Kind::Text),
+            (
+                "pagedir",
+                "G",
+                Kind::OneOf(&["BL", "BR", "TL", "TR", "RB", "RT", "LB", "LT"]),
+            ),
+            ("pencolor", "C", Kind::Colors),
+            ("penwidth", "CNE", Kind::Number(0.0)),
+            ("peripheries", "NC", Kind::Integer(0)),
+            ("pin", "N", Kind::Bool),
+            ("pos", "EN", Kind::Text),
+            ("quadtree", "G", Kind::Text),
+            ("quantum", "G", Kind::Number(0.0)),
+            (
+                "rank",
+                "S",
+                Kind::OneOf(&["same", "min", "source", "max", "sink"]),
+            ),
+            ("rankdir", "G", Kind::OneOf(&["TB", "LR", "BT", "RL"])),
+            ("ranksep", "G", Kind::Text),
+            ("ratio", "G", Kind::Text),
+            ("rects", "N", Kind::Text),
+            ("regular", "N", Kind::Bool),
+            ("remincross", "G", Kind::Bool),
+            ("repulsiveforce", "G", Kind::Number(0.0)),
+            ("resolution", "G", Kind::Number(0.0)),
+            ("root", "GN", Kind::Text),
+            ("rotate", "G", Kind::Integer(i64::MIN)),
+            ("rotation", "G", Kind::Number(f64::MIN)),
+            ("samehead", "E", Kind::Text),
+            ("sametail", "E", Kind::Text),
+            ("samplepoints", "N", Kind::Integer(0)),
+            ("scale", "G", Kind::Text),
+            ("searchsize", "G", Kind::Integer(i64::MIN)),
+            ("sep", "G", Kind::Text),
+            ("shape", "N", Kind::Shape),
+            ("shapefile", "N", Kind::Text),
+            ("showboxes", "ENG", Kind::Integer(0)),
+            ("sides", "N", Kind::Integer(3)),
+            ("size", "G", Kind::Text),
+            ("skew", "N", Kind::Number(-100.0)),
+            ("smoothing", "G", Kind::Text),
+            ("sortv", "GCN", Kind::Integer(0)),
+            (
+                "splines",
+                "G",
+                Kind::OneOf(&[
+                    "none", "line", "false", "polyline", "curved", "ortho", "spline", "true",
+                    "compound", "",
+                ]),
+            ),
+            ("start", "G", Kind::Text),
+            ("style", "ENCG", Kind::Styles),
+            ("stylesheet", "G", Kind::Text),
+            ("tail_lp", "E", Kind::Text),
+            ("tailclip", "E", Kind::Bool),
+            ("tailhref", "E", Kind::Text),
+            ("taillabel", "E", Kind::Text),
+            ("tailport", "E", Kind::Text),
+            ("tailtarget", "E", Kind::Text),
+            ("tailtooltip", "E", Kind::Text),
+            ("tailURL", "E", Kind::Text),
+            ("target", "ENGC", Kind::Text),
+            ("TBbalance", "G", Kind::OneOf(&["min", "max"])),
+            ("tooltip", "NEC", Kind::Text),
+            ("truecolor", "G", Kind::Bool),
+            ("URL", "ENGC", Kind::Text),
+            ("vertices", "N", Kind::Text),
+            ("viewport", "G", Kind::Text),
+            ("voro_margin", "G", Kind::Number(0.0)),
+            ("weight", "E", Kind::Number(0.0)),
+            ("width", "N", Kind::Number(0.01)),
+            ("xdotversion", "G", Kind::Text),
+            ("xlabel", "EN", Kind::Text),
+            ("xlp", "NE", Kind::Text),
+            ("z", "N", Kind::Number(f64::MIN)),
+        ];
+
+        fn check(target: Target, key: &str, value: &str) -> Result<(), AttrError> {
+            let kind = ATTRIBUTES
+                .iter()
+                .find(|(name, used_by, _)| {
+                    *name == key && used_by.chars().any(|c| target.letters().contains(&c))
+                })
+                .map(|(_, _, kind)| *kind)
+                .ok_or_else(|| AttrError::UnknownAttribute(key.to_string()))?;
+            let valid = match kind {
+                Kind::Text => true,
+                Kind::Colors => value.parse::<ColorList>().is_ok(),
+                Kind::Shape => value.parse::<Shape>().is_ok(),
+                Kind::Styles => parse_styles(value)
+                    .is_some_and(|styles| styles.iter().all(|style| style.applies_to(target))),
+                Kind::Number(min) => value
+                    .parse::<f64>()
+                    .is_ok_and(|number| number.is_finite() && number >= min),
+                Kind::Integer(min) => value.parse::<i64>().is_ok_and(|number| number >= min),
+                Kind::Bool => {
+                    matches!(
+                        value.to_ascii_lowercase().as_str(),
+                        "true" | "false" | "yes" | "no"
+                    ) || value.parse::<i64>().is_ok()
+                }
+                Kind::OneOf(values) => values.contains(&value),
+            };
+            match valid {
+                true => Ok(()),
+                false => Err(AttrError::InvalidValue {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                }),
+            }
+        }
+
+        fn parse_styles(value: &str) -> Option<Vec<Style>> {
+            value
+                .split(',')
+                .map(|style| style.trim())
+                .filter(|style| !style.is_empty())
+                .map(|style| style.parse::<Style>().ok())
+                .collect()
+        }
+
+        fn check_all(target: Target, attrs: &HashMap<String, String>) -> Result<(), AttrError> {
+            let mut keys: Vec<&String> = attrs.keys().collect();
+            keys.sort();
+            keys.into_iter()
+                .try_for_each(|key| check(target, key, &attrs[key]))
+        }
+
+        fn get<T: FromStr>(
+            target: Target,
+            attrs: &HashMap<String, String>,
+            key: &str,
+        ) -> Result<Option<T>, AttrError> {
+            let Some(value) = attrs.get(key) else {
+                return Ok(None);
+            };
+            check(target, key, value)?;
+            value
+                .parse::<T>()
+                .map(Some)
+                .map_err(|_| AttrError::InvalidValue {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                })
+        }
+
+        // Methods checking the attributes of an item, and typed getters and setters for
+        // the attributes given as `getter, setter: type = "key"`. The setters take any
+        // value convertible to the type, such as a single `Color` for a `ColorList`.
+        macro_rules! typed_attributes {
+            ($item:ty, $target:expr, $attrs:ident {
+                $($getter:ident, $setter:ident: $value:ty = $key:literal;)*
+            }) => {
+                impl $item {
+                    // Sets an attribute, if it exists for this kind of item and the value
+                    // is valid.
+                    pub fn set_attr(&mut self, key: &str, value: &str) -> Result<(), AttrError> {
+                        check($target, key, value)?;
+                        self.$attrs.insert(key.to_string(), value.to_string());
+                        Ok(())
+                    }
+
+                    pub fn try_with_attrs(mut self, attrs: &[(&str, &str)]) -> Result<Self, AttrError> {
+                        for (key, value) in attrs {
+                            self.set_attr(key, value)?;
+                        }
+                        Ok(self)
+                    }
+
+                    // The first unknown attribute or invalid value, by key.
+                    pub fn validate(&self) -> Result<(), AttrError> {
+                        check_all($target, &self.$attrs)
+                    }
+
+                    pub fn style(&self) -> Result<Vec<Style>, AttrError> {
+                        Ok(get::<String>($target, &self.$attrs, "style")?
+                            .and_then(|styles| parse_styles(&styles))
+                            .unwrap_or_default())
+                    }
+
+                    pub fn set_style(&mut self, styles: &[Style]) -> Result<(), AttrError> {
+                        let styles: Vec<&str> = styles.iter().map(|style| style.keyword()).collect();
+                        self.set_attr("style", &styles.join(","))
+                    }
+
+                    $(
+                        pub fn $getter(&self) -> Result<Option<$value>, AttrError> {
+                            get($target, &self.$attrs, $key)
+                        }
+
+                        pub fn $setter(&mut self, value: impl Into<$value>) -> Result<(), AttrError> {
+                            self.set_attr($key, &value.into().to_string())
+                        }
+                    )*
+                }
+            };
+        }
+
+        typed_attributes!(Node, Target::Node, attrs {
+            label, set_label: String = "label";
+            color, set_color: ColorList = "color";
+            fillcolor, set_fillcolor: ColorList = "fillcolor";
+            fontcolor, set_fontcolor: ColorList = "fontcolor";
+            shape, set_shape: Shape = "shape";
+            penwidth, set_penwidth: f64 = "penwidth";
+            width, set_width: f64 = "width";
+            height, set_height: f64 = "height";
+            fontsize, set_fontsize: f64 = "fontsize";
+        });
+
+        typed_attributes!(Edge, Target::Edge, attrs {
+            label, set_label: String = "label";
+            color, set_color: ColorList = "color";
+            fontcolor, set_fontcolor: ColorList = "fontcolor";
+            penwidth, set_penwidth: f64 = "penwidth";
+            arrowsize, set_arrowsize: f64 = "arrowsize";
+            weight, set_weight: f64 = "weight";
+            fontsize, set_fontsize: f64 = "fontsize";
+        });
+
+        typed_attributes!(Subgraph, Target::Subgraph, attrs {
+            label, set_label: String = "label";
+            color, set_color: ColorList = "color";
+            fillcolor, set_fillcolor: ColorList = "fillcolor";
+            bgcolor, set_bgcolor: ColorList = "bgcolor";
+            penwidth, set_penwidth: f64 = "penwidth";
+        });
+
+        typed_attributes!(Graph, Target::Graph, attrs {
+            label, set_label: String = "label";
+            bgcolor, set_bgcolor: ColorList = "bgcolor";
+            fontcolor, set_fontcolor: ColorList = "fontcolor";
+            fontsize, set_fontsize: f64 = "fontsize";
+            rankdir, set_rankdir: RankDir = "rankdir";
+        });
+
+        impl Graph {
+            // Checks the attributes of the graph and of everything in it, including the
+            // defaults for its nodes and edges.
+            pub fn validate_all(&self) -> Result<(), AttrError> {
+                fn check_subgraphs(subgraphs: &[Subgraph]) -> Result<(), AttrError> {
+                    subgraphs.iter().try_for_each(|sub| {
+                        sub.validate()?;
+                        check_all(Target::Node, &sub.node_attrs)?;
+                        check_all(Target::Edge, &sub.edge_attrs)?;
+                        check_subgraphs(&sub.subgraphs)
+                    })
+                }
+                self.validate()?;
+                check_all(Target::Node, &self.node_attrs)?;
+                check_all(Target::Edge, &self.edge_attrs)?;
+                self.nodes.iter().try_for_each(|node| node.validate())?;
+                self.edges.iter().try_for_each(|edge| edge.validate())?;
+                check_subgraphs(&self.subgraphs)
+            }
+        }
+    }
+
+    // Comparison and merging of two versions of a graph. Nodes are matched by name and
+    // edges by their ends, the n-th edge between two nodes in one graph matching the n-th
+    // one in the other. The ends of undirected edges may be swapped.
+    pub mod diff {
+        use super::graph_items::edge::Edge;
+        use super::graph_items::node::Node;
+        use super::graph_items::subgraph::Subgraph;
+        use super::{Graph, GraphKind};
+        use std::collections::{HashMap, VecDeque};
+
+        // An attribute that was added (`old` is `None`), removed (`new` is `None`) or
+        // given another value.
+        #[derive(Debug, PartialEq, Eq, Clone)]
+        pub struct AttrChange {
+            pub key: String,
+            pub old: Option<String>,
+            pub new: Option<String>,
+        }
+
+        #[derive(Debug, PartialEq, Eq, Clone)]
+        pub struct NodeChange {
+            pub name: String,
+            pub attrs: Vec<AttrChange>,
+        }
+
+        #[derive(Debug, PartialEq, Eq, Clone)]
+        pub struct EdgeChange {
+            pub from: String,
+            pub to: String,
+            pub attrs: Vec<AttrChange>,
+        }
+
+        #[derive(Debug, PartialEq, Eq, Clone)]
+        pub struct Change<T> {
+            pub old: T,
+            pub new: T,
+        }
+
+        // What changed from one graph to another, attribute changes being sorted by key.
+        // Subgraphs are compared as a whole, nested ones included.
+        #[derive(Debug, PartialEq, Eq, Clone, Default)]
+        pub struct GraphDiff {
+            pub kind: Option<Change<GraphKind>>,
+            pub strict: Option<Change<bool>>,
+            pub name: Option<Change<Option<String>>>,
+            pub attrs: Vec<AttrChange>,
+            pub node_attrs: Vec<AttrChange>,
+            pub edge_attrs: Vec<AttrChange>,
+            pub added_nodes: Vec<Node>,
+            pub removed_nodes: Vec<Node>,
+            pub changed_nodes: Vec<NodeChange>,
+            pub added_edges: Vec<Edge>,
+            pub removed_edges: Vec<Edge>,
+            pub changed_edges: Vec<EdgeChange>,
+            pub added_subgraphs: Vec<Subgraph>,
+            pub removed_subgraphs: Vec<Subgraph>,
+            pub changed_subgraphs: Vec<Change<Subgraph>>,
+        }
+
+        impl GraphDiff {
+            pub fn is_empty(&self) -> bool {
+                *self == GraphDiff::default()
+            }
+        }
+
+        // What to do when both graphs give an attribute different values.
+        #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+        pub enum MergePolicy {
+            KeepOurs,
+            TakeTheirs,
+            Fail,
+        }
+
+        #[derive(Debug, PartialEq, Eq, Clone)]
+        pub enum ConflictItem {
+            Graph,
+            NodeDefaults,
+            EdgeDefaults,
+            Node(String),
+            Edge { from: String, to: String },
+            Subgraph(String),
+        }
+
+        #[derive(Debug, PartialEq, Eq, Clone)]
+        pub struct MergeConflict {
+            pub item: ConflictItem,
+            pub key: String,
+            pub ours: String,
+            pub theirs: String,
+        }
+
+        fn attr_changes(
+            old: &HashMap<String, String>,
+            new: &HashMap<String, String>,
+        ) -> Vec<AttrChange> {
+            let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            keys.into_iter()
+                .filter(|key| old.get(*key) != new.get(*key))
+                .map(|key| AttrChange {
+                    key: key.clone(),
+                    old: old.get(key).cloned(),
+                    new: new.get(key).cloned(),
+                })
+                .collect()
+        }
+
+        fn merge_attrs(
+            ours: &mut HashMap<String, String>,
+            theirs: &HashMap<String, String>,
+            policy: MergePolicy,
+            item: impl Fn() -> ConflictItem,
+        ) -> Result<(), MergeConflict> {
+            let mut keys: Vec<&String> = theirs.keys().collect();
+            keys.sort();
+            for key in keys {
+                let value = &theirs[key];
+                match ours.get(key) {
+                    Some(existing) if existing == value => {}
+                    Some(existing) => match policy {
+                        MergePolicy::KeepOurs => {}
+                        MergePolicy::TakeTheirs => {
+                            ours.insert(key.clone(), value.clone());
+                        }
+                        MergePolicy::Fail => {
+                            return Err(MergeConflict {
+                                item: item(),
+                                key: key.clone(),
+                                ours: existing.clone(),
+                                theirs: value.clone(),
+                            })
+                        }
+                    },
+                    None => {
+                        ours.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        // Pairs of indices of matching edges or subgraphs, followed by the indices of the
+        // ones of either graph left without a match.
+        type Matching = (Vec<(usize, usize)>, Vec<usize>, Vec<usize>);
+
+        fn match_edges(kind: GraphKind, ours: &[Edge], theirs: &[Edge]) -> Matching {
+            fn key(kind: GraphKind, edge: &Edge) -> (&str, &str) {
+                let (from, to) = (edge.from.as_str(), edge.to.as_str());
+                match kind == GraphKind::Undirected && to < from {
+                    true => (to, from),
+                    false => (from, to),
+                }
+            }
+            let mut candidates: HashMap<(&str, &str), VecDeque<usize>> = HashMap::new();
+            for (idx, edge) in theirs.iter().enumerate() {
+                candidates
+                    .entry(key(kind, edge))
+                    .or_default()
+                    .push_back(idx);
+            }
+            let mut matched = Vec::new();
+            let mut only_ours = Vec::new();
+            for (idx, edge) in ours.iter().enumerate() {
+                match candidates
+                    .get_mut(&key(kind, edge))
+                    .and_then(|c| c.pop_front())
+                {
+                    Some(other) => matched.push((idx, other)),
+                    None => only_ours.push(idx),
+                }
+            }
+            let mut only_theirs: Vec<usize> = candidates.into_values().flatten().collect();
+            only_theirs.sort_unstable();
+            (matched, only_ours, only_theirs)
+        }
+
+        // Subgraphs are matched by name, the n-th one with a name in one graph matching
+        // the n-th one with the same name in the other, anonymous ones included.
+        fn match_subgraphs(ours: &[Subgraph], theirs: &[Subgraph]) -> Matching {
+            let mut candidates: HashMap<&Option<String>, VecDeque<usize>> = HashMap::new();
+            for (idx, sub) in theirs.iter().enumerate() {
+                candidates.entry(&sub.name).or_default().push_back(idx);
+            }
+            let mut matched = Vec::new();
+            let mut only_ours = Vec::new();
+            for (idx, sub) in ours.iter().enumerate() {
+                match candidates.get_mut(&sub.name).and_then(|c| c.pop_front()) {
+                    Some(other) => matched.push((idx, other)),
+                    None => only_ours.push(idx),
+                }
+            }
+            let mut only_theirs: Vec<usize> = candidates.into_values().flatten().collect();
+            only_theirs.sort_unstable();
+            (matched, only_ours, only_theirs)
+        }
+
+        fn change<T: PartialEq + Clone>(old: &T, new: &T) -> Option<Change<T>> {
+            (old != new).then(|| Change {
+                old: old.clone(),
+                new: new.clone(),
+            })
+        }
+
+        fn merge_subgraphs(
+            ours: &mut Vec<Subgraph>,
+            theirs: &[Subgraph],
+            policy: MergePolicy,
+        ) -> Result<(), MergeConflict> {
+            for sub in theirs {
+                let existing = sub.name.as_ref().and_then(|name| {
+                    ours.iter_mut()
+                        .find(|other| other.name.as_ref() == Some(name))
+                });
+                let Some(existing) = existing else {
+                    ours.push(sub.clone());
+                    continue;
+                };
+                let item = || ConflictItem::Subgraph(sub.name.clone().unwrap_or_default());
+                merge_attrs(&mut existing.attrs, &sub.attrs, policy, item)?;
+                merge_attrs(&mut existing.node_attrs, &sub.node_attrs, policy, item)?;
+                merge_attrs(&mut existing.edge_attrs, &sub.edge_attrs, policy, item)?;
+                for name in &sub.nodes {
+                    if !existing.nodes.contains(name) {
+                        existing.nodes.push(name.clone());
+                    }
+                }
+                merge_subgraphs(&mut existing.subgraphs, &sub.subgraphs, policy)?;
+            }
+            Ok(())
+        }
+
+        impl Graph {
+            // The changes turning this graph into `other`.
+            pub fn diff(&self, other: &Graph) -> GraphDiff {
+                let mut diff = GraphDiff {
+                    kind: change(&self.kind, &other.kind),
+                    strict: change(&self.strict, &other.strict),
+                    name: change(&self.name, &other.name),
+                    attrs: attr_changes(&self.attrs, &other.attrs),
+                    node_attrs: attr_changes(&self.node_attrs, &other.node_attrs),
+                    edge_attrs: attr_changes(&self.edge_attrs, &other.edge_attrs),
+                    ..GraphDiff::default()
+                };
+                for node in &self.nodes {
+                    match other.node(&node.name) {
+                        None => diff.removed_nodes.push(node.clone()),
+                        Some(new) => {
+                            let attrs = attr_changes(&node.attrs, &new.attrs);
+                            if !attrs.is_empty() {
+                                diff.changed_nodes.push(NodeChange {
+                                    name: node.name.clone(),
+                                    attrs,
+                                });
+                            }
+                        }
+                    }
+                }
+                diff.added_nodes = other
+                    .nodes
+                    .iter()
+                    .filter(|node| !self.contains_node(&node.name))
+                    .cloned()
+                    .collect();
+                let (matched, removed, added) = match_edges(self.kind, &self.edges, &other.edges);
+                for (old, new) in matched {
+                    let attrs = attr_changes(&self.edges[old].attrs, &other.edges[new].attrs);
+                    if !attrs.is_empty() {
+                        diff.changed_edges.push(EdgeChange {
+                            from: self.edges[old].from.clone(),
+                            to: self.edges[old].to.clone(),
+                            attrs,
+                        });
+                    }
+                }
+                diff.removed_edges = removed
+                    .into_iter()
+                    .map(|idx| self.edges[idx].clone())
+                    .collect();
+                diff.added_edges = added
+                    .into_iter()
+                    .map(|idx| other.edges[idx].clone())
+                    .collect();
+                let (matched, removed, added) = match_subgraphs(&self.subgraphs, &other.subgraphs);
+                diff.changed_subgraphs = matched
+                    .into_iter()
+                    .filter_map(|(old, new)| change(&self.subgraphs[old], &other.subgraphs[new]))
+                    .collect();
+                diff.removed_subgraphs = removed
+                    .into_iter()
+                    .map(|idx| self.subgraphs[idx].clone())
+                    .collect();
+                diff.added_subgraphs = added
+                    .into_iter()
+                    .map(|idx| other.subgraphs[idx].clone())
+                    .collect();
+                diff
+            }
+
+            // A graph with the nodes, edges, subgraphs and attributes of both graphs,
+            // keeping the kind, name and strictness of this one.
+            pub fn merge(
+                &self,
+                other: &Graph,
+                policy: MergePolicy,
+            ) -> Result<Graph, MergeConflict> {
+                let mut merged = self.clone();
+                merge_attrs(&mut merged.attrs, &other.attrs, policy, || {
+                    ConflictItem::Graph
+                })?;
+                merge_attrs(&mut merged.node_attrs, &other.node_attrs, policy, || {
+                    ConflictItem::NodeDefaults
+                })?;
+                merge_attrs(&mut merged.edge_attrs, &other.edge_attrs, policy, || {
+                    ConflictItem::EdgeDefaults
+                })?;
+                for node in &other.nodes {
+                    let idx = merged.insert_node(&node.name);
+                    merge_attrs(
+                        &mut merged.nodes.node_mut(idx).attrs,
+                        &node.attrs,
+                        policy,
+                        || ConflictItem::Node(node.name.clone()),
+                    )?;
+                }
+                let (matched, _, added) = match_edges(self.kind, &self.edges, &other.edges);
+                for (ours, theirs) in matched {
+                    let edge = &other.edges[theirs];
+                    merge_attrs(&mut merged.edges[ours].attrs, &edge.attrs, policy, || {
+                        ConflictItem::Edge {
+                            from: edge.from.clone(),
+                            to: edge.to.clone(),
+                        }
+                    })?;
+                }
+                merged
+                    .edges
+                    .extend(added.into_iter().map(|idx| other.edges[idx].clone()));
+                merge_subgraphs(&mut merged.subgraphs, &other.subgraphs, policy)?;
+                for sub in &other.subgraphs {
+                    for name in sub.node_names() {
+                        merged.insert_node(name);
+                    }
+                }
+                Ok(merged)
+            }
+        }
+    }
+
+    // JSON form of graphs, with attributes sorted by key so that the same graph always
+    // gives the same text.
+    #[cfg(feature = "serde")]
+    pub mod json {
+        use super::graph_items::edge::Edge;
+        use super::graph_items::node::Node;
+        use super::graph_items::subgraph::Subgraph;
+        use super::{Graph, GraphError, GraphKind};
+        use serde::de::Error;
+        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+        use std::collections::{BTreeMap, HashMap};
+
+        pub(crate) fn ordered<S: Serializer>(
+            attrs: &HashMap<String, String>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            attrs
+                .iter()
+                .collect::<BTreeMap<_, _>>()
+                .serialize(serializer)
+        }
+
+        #[derive(Serialize)]
+        struct GraphRef<'a> {
+            kind: GraphKind,
+            strict: bool,
+            name: &'a Option<String>,
+            #[serde(serialize_with = "ordered")]
+            attrs: &'a HashMap<String, String>,
+            #[serde(serialize_with = "ordered")]
+            node_attrs: &'a HashMap<String, String>,
+            #[serde(serialize_with = "ordered")]
+            edge_attrs: &'a HashMap<String, String>,
+            nodes: &'a [Node],
+            edges: &'a [Edge],
+            subgraphs: &'a [Subgraph],
+        }
+
+        #[derive(Deserialize)]
+        struct GraphData {
+            #[serde(default)]
+            kind: GraphKind,
+            #[serde(default)]
+            strict: bool,
+            #[serde(default)]
+            name: Option<String>,
+            #[serde(default)]
+            attrs: HashMap<String, String>,
+            #[serde(default)]
+            node_attrs: HashMap<String, String>,
+            #[serde(default)]
+            edge_attrs: HashMap<String, String>,
+            #[serde(default)]
+            nodes: Vec<Node>,
+            #[serde(default)]
+            edges: Vec<Edge>,
+            #[serde(default)]
+            subgraphs: Vec<Subgraph>,
+        }
+
+        impl Serialize for Graph {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                GraphRef {
+                    kind: self.kind,
+                    strict: self.strict,
+                    name: &self.name,
+                    attrs: &self.attrs,
+                    node_attrs: &self.node_attrs,
+                    edge_attrs: &self.edge_attrs,
+                    nodes: &self.nodes,
+                    edges: &self.edges,
+                    subgraphs: &self.subgraphs,
+                }
+                .serialize(serializer)
+            }
+        }
 
-    #[derive(Default)]
-    pub struct Graph {
-        pub nodes: Vec<Node>,
-        pub edges: Vec<Edge>,
-        pub attrs: HashMap<String, String>,
+        // The index of the nodes is rebuilt rather than stored. Nodes must have distinct
+        // names, and edges may only join nodes listed or named by a subgraph.
+        impl<'de> Deserialize<'de> for Graph {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let data = GraphData::deserialize(deserializer)?;
+                let graph = Graph {
+                    kind: data.kind,
+                    strict: data.strict,
+                    name: data.name,
+                    attrs: data.attrs,
+                    node_attrs: data.node_attrs,
+                    edge_attrs: data.edge_attrs,
+                    ..Graph::default()
+                };
+                let invalid = |err| match err {
+                    GraphError::DuplicateNode(name) => {
+                        D::Error::custom(format!("duplicate node `{}`", name))
+                    }
+                    GraphError::UnknownNode(name) => {
+                        D::Error::custom(format!("edge to unknown node `{}`", name))
+                    }
+                };
+                graph
+                    .try_with_nodes(&data.nodes)
+                    .map_err(invalid)?
+                    .with_subgraphs(&data.subgraphs)
+                    .try_with_edges(&data.edges)
+                    .map_err(invalid)
+            }
+        }
+
+        impl Graph {
+            pub fn to_json(&self) -> String {
+                serde_json::to_string_pretty(self).expect("graphs always serialize")
+            }
+
+            pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+                serde_json::from_str(json)
+            }
+        }
     }
 
-    impl Graph {
-        pub fn new() -> Self {
-            Self::default()
+    // Layered drawing of a graph in the manner of Sugiyama: cycles are broken by turning
+    // edges around, nodes are put on ranks so that edges point down, long edges get
+    // invisible nodes on the ranks they cross, nodes are ordered within their ranks to
+    // limit crossings and finally moved next to the nodes they are linked to.
+    pub mod layout {
+        use super::Graph;
+        use std::collections::HashMap;
+
+        const CHAR_WIDTH: f64 = 7.0;
+        const LINE_HEIGHT: f64 = 14.0;
+        const PADDING: f64 = 12.0;
+        const MIN_WIDTH: f64 = 54.0;
+        const MIN_HEIGHT: f64 = 36.0;
+        const POINT_SIZE: f64 = 8.0;
+        const POINTS_PER_INCH: f64 = 72.0;
+        const NODE_SEP: f64 = 25.0;
+        const RANK_SEP: f64 = 50.0;
+        const LOOP_SIZE: f64 = 20.0;
+        pub(super) const MARGIN: f64 = 10.0;
+        const ORDERING_SWEEPS: usize = 8;
+        const PLACEMENT_PASSES: usize = 4;
+
+        #[derive(Debug, PartialEq, Clone, Copy)]
+        pub struct Point {
+            pub x: f64,
+            pub y: f64,
         }
 
-        pub fn with_attrs(mut self, attrs: &[(&str, &str)]) -> Self {
-            for (key, value) in attrs {
-                self.attrs.insert(key.to_string(), value.to_string());
+        // A node placed in the drawing, `center` being the middle of its bounding box.
+        #[derive(Debug, PartialEq, Clone)]
+        pub struct NodeLayout {
+            pub name: String,
+            pub rank: usize,
+            pub center: Point,
+            pub width: f64,
+            pub height: f64,
+        }
+
+        // The route of an edge, from the outline of its tail to the outline of its head.
+        #[derive(Debug, PartialEq, Clone)]
+        pub struct EdgeLayout {
+            pub from: String,
+            pub to: String,
+            pub points: Vec<Point>,
+        }
+
+        // Nodes come in the order of the graph, followed by the ones only named by edges,
+        // and edges in the order of the graph.
+        #[derive(Debug, PartialEq, Clone)]
+        pub struct Layout {
+            pub width: f64,
+            pub height: f64,
+            pub nodes: Vec<NodeLayout>,
+            pub edges: Vec<EdgeLayout>,
+        }
+
+        impl Layout {
+            pub fn node(&self, name: &str) -> Option<&NodeLayout> {
+                self.nodes.iter().find(|node| node.name == name)
             }
-            self
         }
 
-        pub fn with_edges(mut self, edges: &Vec<Edge>) -> Self {
-            self.edges = edges.clone();
-            self
+        // The outline drawn around a node, from its `shape` attribute.
+        #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+        pub(super) enum Outline {
+            Box,
+            Ellipse,
+            Circle,
+            Diamond,
+            Point,
+            Plain,
         }
 
-        pub fn with_nodes(mut self, nodes: &Vec<Node>) -> Self {
-            self.nodes = nodes.clone();
-            self
+        impl Outline {
+            pub(super) fn of(shape: Option<&str>) -> Self {
+                match shape {
+                    Some("box" | "rect" | "rectangle" | "square") => Outline::Box,
+                    Some("circle" | "doublecircle") => Outline::Circle,
+                    Some("diamond") => Outline::Diamond,
+                    Some("point") => Outline::Point,
+                    Some("plaintext" | "plain" | "none" | "underline") => Outline::Plain,
+                    _ => Outline::Ellipse,
+                }
+            }
         }
 
-        pub fn node(&self, name: &str) -> Option<Node> {
-            self.nodes.iter().find(|n| n.name.as_str() == name).cloned()
+        // Where rank 0 goes, following the `rankdir` attribute of the graph.
+        #[derive(Clone, Copy, PartialEq, Eq)]
+        enum Direction {
+            TopToBottom,
+            BottomToTop,
+            LeftToRight,
+            RightToLeft,
         }
 
-        pub fn attr(&self, key: &str) -> Option<&str> {
-            self.attrs.get(key).map(|v| v.as_str())
+        impl Direction {
+            fn of(rankdir: Option<&str>) -> Self {
+                match rankdir {
+                    Some("BT") => Direction::BottomToTop,
+                    Some("LR") => Direction::LeftToRight,
+                    Some("RL") => Direction::RightToLeft,
+                    _ => Direction::TopToBottom,
+                }
+            }
+
+            fn is_horizontal(self) -> bool {
+                matches!(self, Direction::LeftToRight | Direction::RightToLeft)
+            }
+        }
+
+        // The lines of the label of a node, which defaults to its name. `\N` stands for
+        // the name, and `\n`, `\l` and `\r` end lines.
+        pub(super) fn label_lines(graph: &Graph, name: &str) -> Vec<String> {
+            let label = graph.node_attr(name, "label").unwrap_or(name);
+            split_lines(&label.replace("\\N", name))
+        }
+
+        pub(super) fn split_lines(label: &str) -> Vec<String> {
+            let mut lines: Vec<String> = label
+                .split('\n')
+                .flat_map(|line| line.split("\\n"))
+                .flat_map(|line| line.split("\\l"))
+                .flat_map(|line| line.split("\\r"))
+                .map(|line| line.to_string())
+                .collect();
+            // A trailing line break does not start another line.
+            if lines.len() > 1 && lines.last().is_some_and(|line| line.is_empty()) {
+                lines.pop();
+            }
+            lines
+        }
+
+        fn node_size(graph: &Graph, name: &str) -> (f64, f64) {
+            let outline = Outline::of(graph.node_attr(name, "shape"));
+            if outline == Outline::Point {
+                return (POINT_SIZE, POINT_SIZE);
+            }
+            let lines = label_lines(graph, name);
+            let longest = lines
+                .iter()
+                .map(|line| line.chars().count())
+                .max()
+                .unwrap_or(0);
+            let mut width = longest as f64 * CHAR_WIDTH + 2.0 * PADDING;
+            let mut height = lines.len() as f64 * LINE_HEIGHT + PADDING;
+            // Curved outlines need more room around the label.
+            let scale = match outline {
+                Outline::Ellipse | Outline::Circle => 1.25,
+                Outline::Diamond => 1.6,
+                _ => 1.0,
+            };
+            width = (width * scale).max(MIN_WIDTH);
+            height = (height * scale).max(MIN_HEIGHT);
+            // `width` and `height` are minimum sizes in inches.
+            let inches = |key| {
+                graph
+                    .node_attr(name, key)
+                    .and_then(|value| value.parse::<f64>().ok())
+                    .map_or(0.0, |value| value * POINTS_PER_INCH)
+            };
+            width = width.max(inches("width"));
+            height = height.max(inches("height"));
+            if outline == Outline::Circle {
+                width = width.max(height);
+                height = width;
+            }
+            (width, height)
+        }
+
+        // The point where the segment from the center of the node towards `toward`
+        // leaves its outline.
+        fn clip(node: &NodeLayout, outline: Outline, toward: Point) -> Point {
+            let (dx, dy) = (toward.x - node.center.x, toward.y - node.center.y);
+            let (half_width, half_height) = (node.width / 2.0, node.height / 2.0);
+            let (rx, ry) = ((dx / half_width).abs(), (dy / half_height).abs());
+            let reach = match outline {
+                Outline::Box | Outline::Plain => rx.max(ry),
+                Outline::Diamond => rx + ry,
+                Outline::Ellipse | Outline::Circle | Outline::Point => rx.hypot(ry),
+            };
+            if reach == 0.0 {
+                return node.center;
+            }
+            Point {
+                x: node.center.x + dx / reach,
+                y: node.center.y + dy / reach,
+            }
+        }
+
+        impl Graph {
+            pub fn layout(&self) -> Layout {
+                let mut names: Vec<&str> = Vec::new();
+                let mut index: HashMap<&str, usize> = HashMap::new();
+                let endpoints = self
+                    .edges
+                    .iter()
+                    .flat_map(|edge| [edge.from.as_str(), edge.to.as_str()]);
+                for name in self.nodes.iter().map(|n| n.name.as_str()).chain(endpoints) {
+                    index.entry(name).or_insert_with(|| {
+                        names.push(name);
+                        names.len() - 1
+                    });
+                }
+                let count = names.len();
+                let direction = Direction::of(self.attr("rankdir"));
+                let sizes: Vec<(f64, f64)> =
+                    names.iter().map(|name| node_size(self, name)).collect();
+                // Extents of the nodes along their rank and across ranks.
+                let (across, along): (Vec<f64>, Vec<f64>) = sizes
+                    .iter()
+                    .map(|&(width, height)| match direction.is_horizontal() {
+                        true => (height, width),
+                        false => (width, height),
+                    })
+                    .unzip();
+
+                let links: Vec<(usize, usize)> = self
+                    .edges
+                    .iter()
+                    .map(|edge| (index[edge.from.as_str()], index[edge.to.as_str()]))
+                    .collect();
+                let reversed = feedback_edges(count, &links);
+                let acyclic: Vec<(usize, usize)> = links
+                    .iter()
+                    .zip(&reversed)
+                    .map(|(&(from, to), &reversed)| match reversed {
+                        true => (to, from),
+                        false => (from, to),
+                    })
+                    .collect();
+                let ranks = assign_ranks(count, &acyclic);
+
+                // Every edge becomes a chain of vertices on consecutive ranks, the ones
+                // past the real nodes standing in for the edge on the ranks it crosses.
+                let mut rank_of = ranks.clone();
+                let mut extent = across.clone();
+                let mut chains = Vec::with_capacity(links.len());
+                for &(from, to) in &acyclic {
+                    let mut chain = vec![from];
+                    if from != to {
+                        for rank in ranks[from] + 1..ranks[to] {
+                            rank_of.push(rank);
+                            extent.push(0.0);
+                            chain.push(rank_of.len() - 1);
+                        }
+                        chain.push(to);
+                    }
+                    chains.push(chain);
+                }
+                let vertices = rank_of.len();
+                let mut up = vec![Vec::new(); vertices];
+                let mut down = vec![Vec::new(); vertices];
+                for chain in &chains {
+                    for pair in chain.windows(2) {
+                        down[pair[0]].push(pair[1]);
+                        up[pair[1]].push(pair[0]);
+                    }
+                }
+                let rank_count = rank_of.iter().max().map_or(0, |rank| rank + 1);
+                let mut layers = vec![Vec::new(); rank_count];
+                for (vertex, rank) in rank_of.iter().enumerate() {
+                    layers[*rank].push(vertex);
+                }
+                order_layers(&mut layers, &up, &down);
+                let offsets = place_across(&layers, &extent, &up, &down);
+
+                // Ranks are as deep as their deepest node.
+                let mut depth = vec![0.0f64; rank_count];
+                for (vertex, rank) in ranks.iter().enumerate() {
+                    depth[*rank] = depth[*rank].max(along[vertex]);
+                }
+                let mut rank_center = Vec::with_capacity(rank_count);
+                let mut cursor = MARGIN;
+                for rank_depth in &depth {
+                    rank_center.push(cursor + rank_depth / 2.0);
+                    cursor += rank_depth + RANK_SEP;
+                }
+                let total_along = cursor - RANK_SEP + MARGIN;
+                let total_across = (0..vertices)
+                    .map(|vertex| offsets[vertex] + extent[vertex] / 2.0)
+                    .fold(0.0, f64::max)
+                    + MARGIN;
+                let (width, height) = match direction.is_horizontal() {
+                    true => (
+                        total_along.max(2.0 * MARGIN),
+                        total_across.max(2.0 * MARGIN),
+                    ),
+                    false => (
+                        total_across.max(2.0 * MARGIN),
+                        total_along.max(2.0 * MARGIN),
+                    ),
+                };
+                let point = |vertex: usize| {
+                    let (a, b) = (offsets[vertex], rank_center[rank_of[vertex]]);
+                    match direction {
+                        Direction::TopToBottom => Point { x: a, y: b },
+                        Direction::BottomToTop => Point {
+                            x: a,
+                            y: height - b,
+                        },
+                        Direction::LeftToRight => Point { x: b, y: a },
+                        Direction::RightToLeft => Point { x: width - b, y: a },
+                    }
+                };
+
+                let nodes: Vec<NodeLayout> = names
+                    .iter()
+                    .enumerate()
+                    .map(|(vertex, name)| NodeLayout {
+                        name: name.to_string(),
+                        rank: ranks[vertex],
+                        center: point(vertex),
+                        width: sizes[vertex].0,
+                        height: sizes[vertex].1,
+                    })
+                    .collect();
+                let outlines: Vec<Outline> = names
+                    .iter()
+                    .map(|name| Outline::of(self.node_attr(name, "shape")))
+                    .collect();
+                let edges = self
+                    .edges
+                    .iter()
+                    .zip(chains.iter().zip(&reversed))
+                    .map(|(edge, (chain, reversed))| {
+                        let mut chain = chain.clone();
+                        if *reversed {
+                            chain.reverse();
+                        }
+                        let (from, to) = (chain[0], *chain.last().unwrap());
+                        let points = match from == to {
+                            true => self_loop(&nodes[from], outlines[from]),
+                            false => {
+                                let mut points: Vec<Point> =
+                                    chain.iter().map(|vertex| point(*vertex)).collect();
+                                let last = points.len() - 1;
+                                points[0] = clip(&nodes[from], outlines[from], points[1]);
+                                points[last] = clip(&nodes[to], outlines[to], points[last - 1]);
+                                points
+                            }
+                        };
+                        EdgeLayout {
+                            from: edge.from.clone(),
+                            to: edge.to.clone(),
+                            points,
+                        }
+                    })
+                    .collect::<Vec<EdgeLayout>>();
+                // Loops stick out of the right side of their node.
+                let width = edges
+                    .iter()
+                    .flat_map(|edge| &edge.points)
+                    .fold(width, |width, point| width.max(point.x + MARGIN));
+                Layout {
+                    width,
+                    height,
+                    nodes,
+                    edges,
+                }
+            }
+        }
+
+        // Edges going back to a node still being explored by a depth first search, which
+        // are turned around to make the graph acyclic. Loops are left alone.
+        fn feedback_edges(count: usize, links: &[(usize, usize)]) -> Vec<bool> {
+            let mut outgoing = vec![Vec::new(); count];
+            for (idx, (from, to)) in links.iter().enumerate() {
+                if from != to {
+                    outgoing[*from].push((*to, idx));
+                }
+            }
+            #[derive(Clone, Copy, PartialEq)]
+            enum State {
+                New,
+                Open,
+                Done,
+            }
+            let mut state = vec![State::New; count];
+            let mut reversed = vec![false; links.len()];
+            for start in 0..count {
+                if state[start] != State::New {
+                    continue;
+                }
+                state[start] = State::Open;
+                let mut stack = vec![(start, 0)];
+                while let Some((node, next)) = stack.last_mut() {
+                    match outgoing[*node].get(*next) {
+                        Some(&(to, idx)) => {
+                            *next += 1;
+                            match state[to] {
+                                State::Open => reversed[idx] = true,
+                                State::New => {
+                                    state[to] = State::Open;
+                                    stack.push((to, 0));
+                                }
+                                State::Done => {}
+                            }
+                        }
+                        None => {
+                            state[*node] = State::Done;
+                            stack.pop();
+                        }
+                    }
+                }
+            }
+            reversed
+        }
+
+        // Ranks every node one below the lowest of its predecessors, in an acyclic graph.
+        fn assign_ranks(count: usize, links: &[(usize, usize)]) -> Vec<usize> {
+            let mut outgoing = vec![Vec::new(); count];
+            let mut in_degree = vec![0; count];
+            for &(from, to) in links.iter().filter(|(from, to)| from != to) {
+                outgoing[from].push(to);
+                in_degree[to] += 1;
+            }
+            let mut ranks = vec![0; count];
+            let mut ready: Vec<usize> = (0..count).filter(|idx| in_degree[*idx] == 0).collect();
+            while let Some(node) = ready.pop() {
+                for &to in &outgoing[node] {
+                    ranks[to] = ranks[to].max(ranks[node] + 1);
+                    in_degree[to] -= 1;
+                    if in_degree[to] == 0 {
+                        ready.push(to);
+                    }
+                }
+            }
+            ranks
+        }
+
+        fn positions(layers: &[Vec<usize>], vertices: usize) -> Vec<usize> {
+            let mut positions = vec![0; vertices];
+            for layer in layers {
+                for (position, vertex) in layer.iter().enumerate() {
+                    positions[*vertex] = position;
+                }
+            }
+            positions
+        }
+
+        fn crossings(layers: &[Vec<usize>], down: &[Vec<usize>]) -> usize {
+            let positions = positions(layers, down.len());
+            let mut total = 0;
+            for layer in layers {
+                let segments: Vec<(usize, usize)> = layer
+                    .iter()
+                    .flat_map(|from| {
+                        down[*from]
+                            .iter()
+                            .map(|to| (positions[*from], positions[*to]))
+                    })
+                    .collect();
+                for (i, (a, b)) in segments.iter().enumerate() {
+                    total += segments[i + 1..]
+                        .iter()
+                        .filter(|(c, d)| (a < c && b > d) || (a > c && b < d))
+                        .count();
+                }
+            }
+            total
+        }
+
+        // Sorts the vertices of every rank by the mean position of their neighbours on
+        // the rank above, then on the rank below, keeping the order with the fewest
+        // crossings.
+        fn order_layers(layers: &mut [Vec<usize>], up: &[Vec<usize>], down: &[Vec<usize>]) {
+            let mut best = layers.to_vec();
+            let mut fewest = crossings(layers, down);
+            for sweep in 0..ORDERING_SWEEPS {
+                if fewest == 0 {
+                    break;
+                }
+                let ranks: Vec<usize> = match sweep % 2 {
+                    0 => (1..layers.len()).collect(),
+                    _ => (0..layers.len().saturating_sub(1)).rev().collect(),
+                };
+                let neighbours = if sweep % 2 == 0 { up } else { down };
+                for rank in ranks {
+                    let positions = positions(layers, up.len());
+                    let barycenter = |vertex: usize| {
+                        let around = &neighbours[vertex];
+                        match around.is_empty() {
+                            true => positions[vertex] as f64,
+                            false => {
+                                around.iter().map(|n| positions[*n] as f64).sum::<f64>()
+                                    / around.len() as f64
+                            }
+                        }
+                    };
+                    layers[rank].sort_by(|a, b| barycenter(*a).total_cmp(&barycenter(*b)));
+                }
+                let count = crossings(layers, down);
+                if count < fewest {
+                    fewest = count;
+                    best = layers.to_vec();
+                }
+            }
+            layers.clone_from_slice(&best);
+        }
+
+        // Offsets of the centers of the vertices along their rank. Every vertex is drawn
+        // towards the mean offset of its neighbours, while keeping its rank in order and
+        // apart.
+        fn place_across(
+            layers: &[Vec<usize>],
+            extent: &[f64],
+            up: &[Vec<usize>],
+            down: &[Vec<usize>],
+        ) -> Vec<f64> {
+            let mut offsets = vec![0.0; extent.len()];
+            let gap = |a: usize, b: usize| (extent[a] + extent[b]) / 2.0 + NODE_SEP;
+            let widths: Vec<f64> = layers
+                .iter()
+                .map(|layer| {
+                    layer.iter().map(|v| extent[*v]).sum::<f64>()
+                        + NODE_SEP * layer.len().saturating_sub(1) as f64
+                })
+                .collect();
+            let widest = widths.iter().copied().fold(0.0, f64::max);
+            for (layer, width) in layers.iter().zip(&widths) {
+                let mut cursor = (widest - width) / 2.0;
+                for vertex in layer {
+                    offsets[*vertex] = cursor + extent[*vertex] / 2.0;
+                    cursor += extent[*vertex] + NODE_SEP;
+                }
+            }
+            for pass in 0..PLACEMENT_PASSES {
+                let (ranks, neighbours): (Vec<usize>, _) = match pass % 2 {
+                    0 => ((1..layers.len()).collect(), up),
+                    _ => ((0..layers.len().saturating_sub(1)).rev().collect(), down),
+                };
+                for rank in ranks {
+                    let layer = &layers[rank];
+                    let desired: Vec<f64> = layer
+                        .iter()
+                        .map(|vertex| {
+                            let around = &neighbours[*vertex];
+                            match around.is_empty() {
+                                true => offsets[*vertex],
+                                false => {
+                                    around.iter().map(|n| offsets[*n]).sum::<f64>()
+                                        / around.len() as f64
+                                }
+                            }
+                        })
+                        .collect();
+                    // Pushing the vertices apart from the left and from the right gives
+                    // two valid placements, and so does their mean.
+                    let mut from_left = desired.clone();
+                    for i in 1..layer.len() {
+                        from_left[i] =
+                            from_left[i].max(from_left[i - 1] + gap(layer[i - 1], layer[i]));
+                    }
+                    let mut from_right = desired;
+                    for i in (0..layer.len().saturating_sub(1)).rev() {
+                        from_right[i] =
+                            from_right[i].min(from_right[i + 1] - gap(layer[i], layer[i + 1]));
+                    }
+                    for (i, vertex) in layer.iter().enumerate() {
+                        offsets[*vertex] = (from_left[i] + from_right[i]) / 2.0;
+                    }
+                }
+            }
+            let leftmost = (0..extent.len())
+                .map(|vertex| offsets[vertex] - extent[vertex] / 2.0)
+                .fold(f64::INFINITY, f64::min);
+            if leftmost.is_finite() {
+                for offset in &mut offsets {
+                    *offset += MARGIN - leftmost;
+                }
+            }
+            offsets
+        }
+
+        // A loop leaving the node on its right and coming back a little lower.
+        fn self_loop(node: &NodeLayout, outline: Outline) -> Vec<Point> {
+            let (x, y) = (node.center.x, node.center.y);
+            let right = x + node.width / 2.0;
+            let rise = node.height / 4.0;
+            vec![
+                clip(
+                    node,
+                    outline,
+                    Point {
+                        x: right,
+                        y: y - rise,
+                    },
+                ),
+                Point {
+                    x: right + LOOP_SIZE,
+                    y: y - rise,
+                },
+                Point {
+                    x: right + LOOP_SIZE,
+                    y: y + rise,
+                },
+                clip(
+                    node,
+                    outline,
+                    Point {
+                        x: right,
+                        y: y + rise,
+                    },
+                ),
+            ]
+        }
+    }
+
+    // Drawing of a graph as SVG, from its layout. Nodes and edges honour the `label`,
+    // `color`, `fillcolor`, `fontcolor`, `penwidth`, `shape` and `style` attributes.
+    pub mod svg {
+        use super::graph_items::edge::Edge;
+        use super::layout::{
+            label_lines, split_lines, EdgeLayout, NodeLayout, Outline, Point, MARGIN,
+        };
+        use super::{Graph, GraphKind};
+
+        const FONT_SIZE: f64 = 14.0;
+        const LINE_HEIGHT: f64 = 14.0;
+        const ARROW_LENGTH: f64 = 10.0;
+        const ARROW_WIDTH: f64 = 4.0;
+
+        // Presentation attributes shared by the outlines of nodes and the lines of edges.
+        struct Stroke {
+            color: String,
+            width: f64,
+            dash: Option<&'static str>,
+            filled: bool,
+            rounded: bool,
+            invisible: bool,
+        }
+
+        impl Stroke {
+            fn new(color: Option<&str>, penwidth: Option<&str>, style: Option<&str>) -> Self {
+                let styles: Vec<&str> = style
+                    .unwrap_or("")
+                    .split(',')
+                    .map(|style| style.trim())
+                    .collect();
+                let has = |name| styles.contains(&name);
+                let mut width = penwidth
+                    .and_then(|width| width.parse::<f64>().ok())
+                    .unwrap_or(1.0);
+                if has("bold") {
+                    width = width.max(2.0);
+                }
+                Stroke {
+                    color: color.unwrap_or("black").to_string(),
+                    width,
+                    dash: match (has("dashed"), has("dotted")) {
+                        (true, _) => Some("5,2"),
+                        (_, true) => Some("1,5"),
+                        _ => None,
+                    },
+                    filled: has("filled"),
+                    rounded: has("rounded"),
+                    invisible: has("invis"),
+                }
+            }
+
+            fn attrs(&self, fill: &str) -> String {
+                let mut attrs = format!(
+                    "fill=\"{}\" stroke=\"{}\"",
+                    escape(fill),
+                    escape(&self.color)
+                );
+                if self.width != 1.0 {
+                    attrs.push_str(&format!(" stroke-width=\"{}\"", number(self.width)));
+                }
+                if let Some(dash) = self.dash {
+                    attrs.push_str(&format!(" stroke-dasharray=\"{}\"", dash));
+                }
+                attrs
+            }
+        }
+
+        impl Graph {
+            pub fn to_svg(&self) -> String {
+                let layout = self.layout();
+                let label = self.attr("label").map(split_lines);
+                let label_height = label
+                    .as_ref()
+                    .map_or(0.0, |lines| lines.len() as f64 * LINE_HEIGHT + MARGIN);
+                let (width, height) = (layout.width, layout.height + label_height);
+                let mut svg = format!(
+                    "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n",
+                    w = number(width),
+                    h = number(height)
+                );
+                svg.push_str("<g class=\"graph\">\n");
+                if let Some(name) = &self.name {
+                    svg.push_str(&format!("<title>{}</title>\n", escape(name)));
+                }
+                if let Some(color) = self.attr("bgcolor") {
+                    svg.push_str(&format!(
+                        "<rect x=\"0\" y=\"0\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+                        number(width),
+                        number(height),
+                        escape(color)
+                    ));
+                }
+                for (edge, route) in self.edges.iter().zip(&layout.edges) {
+                    self.draw_edge(&mut svg, edge, route);
+                }
+                for node in &layout.nodes {
+                    self.draw_node(&mut svg, node);
+                }
+                if let Some(lines) = label {
+                    let top = layout.height + LINE_HEIGHT / 2.0;
+                    svg.push_str(&text(
+                        &lines,
+                        Point {
+                            x: width / 2.0,
+                            y: top + (lines.len() as f64 * LINE_HEIGHT) / 2.0,
+                        },
+                        self.attr("fontcolor").unwrap_or("black"),
+                    ));
+                }
+                svg.push_str("</g>\n</svg>\n");
+                svg
+            }
+
+            fn draw_node(&self, svg: &mut String, node: &NodeLayout) {
+                let name = node.name.as_str();
+                let attr = |key| self.node_attr(name, key);
+                let stroke = Stroke::new(attr("color"), attr("penwidth"), attr("style"));
+                if stroke.invisible {
+                    return;
+                }
+                let outline = Outline::of(attr("shape"));
+                let fill = match stroke.filled || outline == Outline::Point {
+                    true => attr("fillcolor").or(attr("color")).unwrap_or("lightgrey"),
+                    false => "none",
+                };
+                let Point { x, y } = node.center;
+                let (half_width, half_height) = (node.width / 2.0, node.height / 2.0);
+                svg.push_str(&format!(
+                    "<g class=\"node\">\n<title>{}</title>\n",
+                    escape(name)
+                ));
+                let shape = match outline {
+                    Outline::Box => format!(
+                        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"{} {}/>\n",
+                        number(x - half_width),
+                        number(y - half_height),
+                        number(node.width),
+                        number(node.height),
+                        match stroke.rounded {
+                            true => " rx=\"6\" ry=\"6\"",
+                            false => "",
+                        },
+                        stroke.attrs(fill)
+                    ),
+                    Outline::Ellipse | Outline::Circle | Outline::Point => format!(
+                        "<ellipse cx=\"{}\" cy=\"{}\" rx=\"{}\" ry=\"{}\" {}/>\n",
+                        number(x),
+                        number(y),
+                        number(half_width),
+                        number(half_height),
+                        stroke.attrs(fill)
+                    ),
+                    Outline::Diamond => format!(
+                        "<polygon points=\"{}\" {}/>\n",
+                        points(&[
+                            Point {
+                                x,
+                                y: y - half_height
+                            },
+                            Point {
+                                x: x + half_width,
+                                y
+                            },
+                            Point {
+                                x,
+                                y: y + half_height
+                            },
+                            Point {
+                                x: x - half_width,
+                                y
+                            },
+                        ]),
+                        stroke.attrs(fill)
+                    ),
+                    Outline::Plain => String::new(),
+                };
+                svg.push_str(&shape);
+                if outline != Outline::Point {
+                    svg.push_str(&text(
+                        &label_lines(self, name),
+                        node.center,
+                        attr("fontcolor").unwrap_or("black"),
+                    ));
+                }
+                svg.push_str("</g>\n");
+            }
+
+            fn draw_edge(&self, svg: &mut String, edge: &Edge, route: &EdgeLayout) {
+                let attr = |key| self.edge_attr(edge, key);
+                let stroke = Stroke::new(attr("color"), attr("penwidth"), attr("style"));
+                if stroke.invisible || route.points.len() < 2 {
+                    return;
+                }
+                let operator = match self.kind {
+                    GraphKind::Directed => "-&gt;",
+                    GraphKind::Undirected => "--",
+                };
+                svg.push_str(&format!(
+                    "<g class=\"edge\">\n<title>{}{}{}</title>\n",
+                    escape(&edge.from),
+                    operator,
+                    escape(&edge.to)
+                ));
+                let mut line = route.points.clone();
+                let mut arrow = None;
+                if self.kind == GraphKind::Directed {
+                    // The line stops at the base of the arrow head, whose tip touches the
+                    // head node.
+                    let tip = *line.last().unwrap();
+                    let before = line[line.len() - 2];
+                    let (dx, dy) = (tip.x - before.x, tip.y - before.y);
+                    let length = dx.hypot(dy);
+                    if length > 0.0 {
+                        let (ux, uy) = (dx / length, dy / length);
+                        let base = Point {
+                            x: tip.x - ux * ARROW_LENGTH.min(length),
+                            y: tip.y - uy * ARROW_LENGTH.min(length),
+                        };
+                        *line.last_mut().unwrap() = base;
+                        arrow = Some([
+                            tip,
+                            Point {
+                                x: base.x - uy * ARROW_WIDTH,
+                                y: base.y + ux * ARROW_WIDTH,
+                            },
+                            Point {
+                                x: base.x + uy * ARROW_WIDTH,
+                                y: base.y - ux * ARROW_WIDTH,
+                            },
+                        ]);
+                    }
+                }
+                svg.push_str(&format!(
+                    "<polyline points=\"{}\" {}/>\n",
+                    points(&line),
+                    stroke.attrs("none")
+                ));
+                if let Some(arrow) = arrow {
+                    svg.push_str(&format!(
+                        "<polygon points=\"{}\" fill=\"{c}\" stroke=\"{c}\"/>\n",
+                        points(&arrow),
+                        c = escape(&stroke.color)
+                    ));
+                }
+                if let Some(label) = attr("label") {
+                    svg.push_str(&text(
+                        &split_lines(label),
+                        midpoint(&route.points),
+                        attr("fontcolor").unwrap_or("black"),
+                    ));
+                }
+                svg.push_str("</g>\n");
+            }
+        }
+
+        // Lines of text centered on `center`.
+        fn text(lines: &[String], center: Point, color: &str) -> String {
+            let top = center.y - (lines.len() as f64 - 1.0) * LINE_HEIGHT / 2.0;
+            lines
+                .iter()
+                .enumerate()
+                .map(|(i, line)| {
+                    format!(
+                        "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\" font-family=\"Times,serif\" font-size=\"{}\" fill=\"{}\">{}</text>\n",
+                        number(center.x),
+                        number(top + i as f64 * LINE_HEIGHT),
+                        number(FONT_SIZE),
+                        escape(color),
+                        escape(line)
+                    )
+                })
+                .collect()
+        }
+
+        // The point halfway along a polyline.
+        fn midpoint(points: &[Point]) -> Point {
+            let lengths: Vec<f64> = points
+                .windows(2)
+                .map(|pair| (pair[1].x - pair[0].x).hypot(pair[1].y - pair[0].y))
+                .collect();
+            let mut remaining = lengths.iter().sum::<f64>() / 2.0;
+            for (pair, length) in points.windows(2).zip(&lengths) {
+                if remaining <= *length && *length > 0.0 {
+                    let t = remaining / length;
+                    return Point {
+                        x: pair[0].x + (pair[1].x - pair[0].x) * t,
+                        y: pair[0].y + (pair[1].y - pair[0].y) * t,
+                    };
+                }
+                remaining -= length;
+            }
+            points[0]
+        }
+
+        fn points(points: &[Point]) -> String {
+            points
+                .iter()
+                .map(|point| format!("{},{}", number(point.x), number(point.y)))
+                .collect::<Vec<_>>()
+                .join(" ")
+        }
+
+        // Numbers with at most two decimals, without trailing zeros.
+        fn number(value: f64) -> String {
+            let text = format!("{:.2}", value);
+            let text = text.trim_end_matches('0').trim_end_matches('.');
+            match text {
+                "-0" => "0".to_string(),
+                _ => text.to_string(),
+            }
+        }
+
+        fn escape(text: &str) -> String {
+            text.replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+                .replace('"', "&quot;")
+                .replace('\'', "&apos;")
+        }
+    }
+
+    // Support for the `graph!` macro, which checks through the types of the builder that
+    // a graph does not mix directed and undirected edges.
+    #[doc(hidden)]
+    pub mod dsl {
+        use super::graph_items::edge::Edge;
+        use super::graph_items::node::Node;
+        use super::{Graph, GraphKind};
+        use std::collections::HashMap;
+        use std::marker::PhantomData;
+
+        pub struct Unknown;
+        pub struct Directed;
+        pub struct Undirected;
+
+        pub trait Kind {
+            const KIND: GraphKind;
+        }
+
+        impl Kind for Unknown {
+            const KIND: GraphKind = GraphKind::Undirected;
+        }
+
+        impl Kind for Directed {
+            const KIND: GraphKind = GraphKind::Directed;
+        }
+
+        impl Kind for Undirected {
+            const KIND: GraphKind = GraphKind::Undirected;
+        }
+
+        #[diagnostic::on_unimplemented(
+            message = "a graph cannot have both `->` and `--` edges",
+            label = "this edge does not match the earlier ones"
+        )]
+        pub trait Accepts<Op> {}
+
+        impl Accepts<Directed> for Unknown {}
+        impl Accepts<Directed> for Directed {}
+        impl Accepts<Undirected> for Unknown {}
+        impl Accepts<Undirected> for Undirected {}
+
+        // Builds a graph statement by statement, with the defaults set by `node_attrs`
+        // and `edge_attrs` applying to the nodes and edges created afterwards, like the
+        // parser does.
+        pub struct Builder<K> {
+            graph: Graph,
+            nodes: Vec<Node>,
+            edges: Vec<Edge>,
+            // The defaults in scope when each node and edge was created.
+            node_defaults: HashMap<String, HashMap<String, String>>,
+            edge_defaults: Vec<HashMap<String, String>>,
+            kind: PhantomData<K>,
+        }
+
+        impl Builder<Unknown> {
+            pub fn new() -> Self {
+                Self {
+                    graph: Graph::new(),
+                    nodes: Vec::new(),
+                    edges: Vec::new(),
+                    node_defaults: HashMap::new(),
+                    edge_defaults: Vec::new(),
+                    kind: PhantomData,
+                }
+            }
+        }
+
+        impl Default for Builder<Unknown> {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl<K: Kind> Builder<K> {
+            pub fn attrs(mut self, attrs: &[(&str, &str)]) -> Self {
+                self.graph = self.graph.with_attrs(attrs);
+                self
+            }
+
+            pub fn node_attrs(mut self, attrs: &[(&str, &str)]) -> Self {
+                self.graph = self.graph.with_node_attrs(attrs);
+                self
+            }
+
+            pub fn edge_attrs(mut self, attrs: &[(&str, &str)]) -> Self {
+                self.graph = self.graph.with_edge_attrs(attrs);
+                self
+            }
+
+            pub fn node(mut self, name: &str, attrs: &[(&str, &str)]) -> Self {
+                self.mention(name);
+                self.nodes.push(Node::new(name).with_attrs(attrs));
+                self
+            }
+
+            // An edge between every consecutive pair of `path`, all with the same
+            // attributes. Nodes are created as they are mentioned, like in DOT.
+            pub fn edges<Op>(mut self, path: &[&str], attrs: &[(&str, &str)]) -> Builder<Op>
+            where
+                K: Accepts<Op>,
+            {
+                for name in path {
+                    self.mention(name);
+                }
+                self.nodes.extend(path.iter().map(|name| Node::new(name)));
+                for pair in path.windows(2) {
+                    self.edges
+                        .push(Edge::new(pair[0], pair[1]).with_attrs(attrs));
+                    self.edge_defaults.push(self.graph.edge_attrs.clone());
+                }
+                Builder {
+                    graph: self.graph,
+                    nodes: self.nodes,
+                    edges: self.edges,
+                    node_defaults: self.node_defaults,
+                    edge_defaults: self.edge_defaults,
+                    kind: PhantomData,
+                }
+            }
+
+            // Records the defaults a node is created with when it is first mentioned.
+            fn mention(&mut self, name: &str) {
+                if !self.node_defaults.contains_key(name) {
+                    let defaults = self.graph.node_attrs.clone();
+                    self.node_defaults.insert(name.to_string(), defaults);
+                }
+            }
+
+            pub fn build(self) -> Graph {
+                let mut graph = self
+                    .graph
+                    .with_kind(K::KIND)
+                    .with_nodes(&self.nodes)
+                    .with_edges(&self.edges);
+                graph.settle_defaults(&self.node_defaults, &self.edge_defaults);
+                graph
+            }
         }
     }
 
@@ -46,9 +4329,14 @@ pub mod graph {
             use std::collections::HashMap;
 
             #[derive(Clone, PartialEq, Eq, Debug)]
+            #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
             pub struct Edge {
                 pub from: String,
                 pub to: String,
+                #[cfg_attr(
+                    feature = "serde",
+                    serde(default, serialize_with = "crate::graph::json::ordered")
+                )]
                 pub attrs: HashMap<String, String>,
             }
 
@@ -78,8 +4366,13 @@ pub mod graph {
             use std::collections::HashMap;
 
             #[derive(Clone, PartialEq, Eq, Debug)]
+            #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
             pub struct Node {
                 pub name: String,
+                #[cfg_attr(
+                    feature = "serde",
+                    serde(default, serialize_with = "crate::graph::json::ordered")
+                )]
                 pub attrs: HashMap<String, String>,
             }
 
@@ -103,5 +4396,214 @@ pub mod graph {
                 }
             }
         }
+
+        pub mod subgraph {
+            use std::collections::HashMap;
+
+            // A group of nodes of a graph, referred to by name, that may hold subgraphs of
+            // its own. Its defaults apply to its nodes and to the edges between them.
+            #[derive(Clone, PartialEq, Eq, Debug, Default)]
+            #[cfg_attr(
+                feature = "serde",
+                derive(serde::Serialize, serde::Deserialize),
+                serde(default)
+            )]
+            pub struct Subgraph {
+                pub name: Option<String>,
+                pub nodes: Vec<String>,
+                pub subgraphs: Vec<Subgraph>,
+                #[cfg_attr(
+                    feature = "serde",
+                    serde(default, serialize_with = "crate::graph::json::ordered")
+                )]
+                pub attrs: HashMap<String, String>,
+                #[cfg_attr(
+                    feature = "serde",
+                    serde(default, serialize_with = "crate::graph::json::ordered")
+                )]
+                pub node_attrs: HashMap<String, String>,
+                #[cfg_attr(
+                    feature = "serde",
+                    serde(default, serialize_with = "crate::graph::json::ordered")
+                )]
+                pub edge_attrs: HashMap<String, String>,
+            }
+
+            impl Subgraph {
+                pub fn new(name: &str) -> Self {
+                    Self {
+                        name: Some(name.to_string()),
+                        ..Self::default()
+                    }
+                }
+
+                pub fn anonymous() -> Self {
+                    Self::default()
+                }
+
+                pub fn with_nodes(mut self, nodes: &[&str]) -> Self {
+                    self.nodes = nodes.iter().map(|name| name.to_string()).collect();
+                    self
+                }
+
+                pub fn with_subgraphs(mut self, subgraphs: &[Subgraph]) -> Self {
+                    self.subgraphs = subgraphs.to_vec();
+                    self
+                }
+
+                pub fn with_attrs(mut self, attrs: &[(&str, &str)]) -> Self {
+                    for (key, value) in attrs {
+                        self.attrs.insert(key.to_string(), value.to_string());
+                    }
+                    self
+                }
+
+                pub fn with_node_attrs(mut self, attrs: &[(&str, &str)]) -> Self {
+                    for (key, value) in attrs {
+                        self.node_attrs.insert(key.to_string(), value.to_string());
+                    }
+                    self
+                }
+
+                pub fn with_edge_attrs(mut self, attrs: &[(&str, &str)]) -> Self {
+                    for (key, value) in attrs {
+                        self.edge_attrs.insert(key.to_string(), value.to_string());
+                    }
+                    self
+                }
+
+                pub fn attr(&self, key: &str) -> Option<&str> {
+                    self.attrs.get(key).map(|v| v.as_str())
+                }
+
+                // Clusters are drawn in a box of their own by the layout engines.
+                pub fn is_cluster(&self) -> bool {
+                    self.name
+                        .as_deref()
+                        .is_some_and(|name| name.starts_with("cluster"))
+                }
+
+                // Whether the node belongs to the subgraph or to one nested in it.
+                pub fn contains(&self, name: &str) -> bool {
+                    self.nodes.iter().any(|n| n == name)
+                        || self.subgraphs.iter().any(|sub| sub.contains(name))
+                }
+
+                pub(crate) fn node_names(&self) -> Vec<&str> {
+                    let mut names: Vec<&str> = self.nodes.iter().map(|n| n.as_str()).collect();
+                    for sub in &self.subgraphs {
+                        names.extend(sub.node_names());
+                    }
+                    names
+                }
+            }
+        }
     }
 }
+
+// Builds a graph from statements in a syntax close to DOT, each ending with `;`:
+//
+//     graph! {
+//         graph [rankdir = "LR"];
+//         node [shape = "box"];
+//         a [color = "red"];
+//         a -> b -> c [style = "dotted"];
+//     }
+//
+// Node names are identifiers or literals, attribute values any expression that can be
+// turned into a string. Edges written with `->` make a directed graph and edges written
+// with `--` an undirected one; mixing both does not compile.
+#[macro_export]
+macro_rules! graph {
+    ($($statements: tt)*) => {
+        $crate::__graph_statements!(
+            @build ($crate::graph::dsl::Builder::new()) $($statements)*
+        )
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __graph_statements {
+    (@build ($($builder: tt)*)) => {
+        $($builder)*.build()
+    };
+    (@build ($($builder: tt)*) graph [$($attrs: tt)*]; $($rest: tt)*) => {
+        $crate::__graph_statements!(
+            @build ($($builder)*.attrs($crate::__graph_attrs!($($attrs)*))) $($rest)*
+        )
+    };
+    (@build ($($builder: tt)*) node [$($attrs: tt)*]; $($rest: tt)*) => {
+        $crate::__graph_statements!(
+            @build ($($builder)*.node_attrs($crate::__graph_attrs!($($attrs)*))) $($rest)*
+        )
+    };
+    (@build ($($builder: tt)*) edge [$($attrs: tt)*]; $($rest: tt)*) => {
+        $crate::__graph_statements!(
+            @build ($($builder)*.edge_attrs($crate::__graph_attrs!($($attrs)*))) $($rest)*
+        )
+    };
+    (@build ($($builder: tt)*)
+        $from: tt $(-> $to: tt)+ $([$($attrs: tt)*])?; $($rest: tt)*) => {
+        $crate::__graph_statements!(
+            @build ($($builder)*.edges::<$crate::graph::dsl::Directed>(
+                &[$crate::__graph_id!($from) $(, $crate::__graph_id!($to))+],
+                $crate::__graph_attrs!($($($attrs)*)?),
+            )) $($rest)*
+        )
+    };
+    (@build ($($builder: tt)*)
+        $from: tt $(-- $to: tt)+ $([$($attrs: tt)*])?; $($rest: tt)*) => {
+        $crate::__graph_statements!(
+            @build ($($builder)*.edges::<$crate::graph::dsl::Undirected>(
+                &[$crate::__graph_id!($from) $(, $crate::__graph_id!($to))+],
+                $crate::__graph_attrs!($($($attrs)*)?),
+            )) $($rest)*
+        )
+    };
+    (@build ($($builder: tt)*) $name: tt $([$($attrs: tt)*])?; $($rest: tt)*) => {
+        $crate::__graph_statements!(
+            @build ($($builder)*.node(
+                $crate::__graph_id!($name),
+                $crate::__graph_attrs!($($($attrs)*)?),
+            )) $($rest)*
+        )
+    };
+    (@build ($($builder: tt)*) $($rest: tt)+) => {
+        compile_error!(concat!(
+            "expected a statement ending with `;` in `graph!`, found: ",
+            stringify!($($rest)+)
+        ))
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __graph_id {
+    ($id: ident) => {
+        stringify!($id)
+    };
+    ($id: literal) => {
+        $id.to_string().as_str()
+    };
+    ($($other: tt)*) => {
+        compile_error!(concat!(
+            "expected an identifier or a literal as node name in `graph!`, found: ",
+            stringify!($($other)*)
+        ))
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __graph_attrs {
+    ($($key: ident = $value: expr),* $(,)?) => {
+        &[$((stringify!($key), $value.to_string().as_str())),*]
+    };
+    ($($other: tt)*) => {
+        compile_error!(concat!(
+            "expected attributes such as `[color = \"red\"]` in `graph!`, found: ",
+            stringify!([$($other)*])
+        ))
+    };
+}
@@ -1,6 +1,15 @@
+use dot_dsl::graph;
+use dot_dsl::graph::algorithms::{AlgorithmError, Path};
+use dot_dsl::graph::attributes::{AttrError, Color, ColorList, RankDir, Shape, Style};
+use dot_dsl::graph::diff::{
+    AttrChange, Change, ConflictItem, EdgeChange, GraphDiff, MergeConflict, MergePolicy, NodeChange,
+};
 use dot_dsl::graph::graph_items::edge::Edge;
 use dot_dsl::graph::graph_items::node::Node;
-use dot_dsl::graph::Graph;
+use dot_dsl::graph::graph_items::subgraph::Subgraph;
+use dot_dsl::graph::layout::Layout;
+use dot_dsl::graph::parser::{ParseError, ParseErrorKind};
+use dot_dsl::graph::{Graph, GraphError, GraphKind};
 use maplit::hashmap;
 
 #[test]
@@ -203,3 +212,1428 @@ fn test_graph_nodes_store_attributes() {
     assert_eq!(c.attr("bat"), None);
     assert_eq!(c.attr("bim"), Some("bef"));
 }
+
+#[test]
+#[ignore]
+fn test_empty_graph_to_dot() {
+    assert_eq!(Graph::new().to_dot(), "graph {\n}");
+    assert_eq!(
+        Graph::new().with_kind(GraphKind::Directed).to_dot(),
+        "digraph {\n}"
+    );
+}
+
+#[test]
+#[ignore]
+fn test_graph_to_dot() {
+    let graph = Graph::new()
+        .with_nodes(&[
+            Node::new("a").with_attrs(&[("color", "red"), ("label", "A node")]),
+            Node::new("b"),
+        ])
+        .with_edges(&[Edge::new("a", "b").with_attrs(&[("weight", "2.5")])])
+        .with_attrs(&[("rankdir", "LR"), ("bgcolor", "yellow")]);
+
+    let expected = "\
+graph {
+    graph [bgcolor=yellow, rankdir=LR]
+    a [color=red, label=\"A node\"]
+    b
+    a -- b [weight=2.5]
+}";
+    assert_eq!(graph.to_dot(), expected);
+    assert_eq!(graph.to_string(), expected);
+}
+
+#[test]
+#[ignore]
+fn test_directed_graph_to_dot() {
+    let graph = Graph::new()
+        .with_kind(GraphKind::Directed)
+        .with_edges(&[Edge::new("a", "b"), Edge::new("b", "c")]);
+
+    assert_eq!(graph.to_dot(), "digraph {\n    a -> b\n    b -> c\n}");
+}
+
+#[test]
+#[ignore]
+fn test_ids_are_quoted_and_escaped_when_needed() {
+    let graph = Graph::new().with_nodes(&[
+        Node::new("node_1"),
+        Node::new("-1.5"),
+        Node::new("2nd"),
+        Node::new("node"),
+        Node::new("say \"hi\"\nthere"),
+        Node::new("<<b>bold</b>>"),
+        Node::new("<a><b>"),
+        Node::new(""),
+    ]);
+
+    let expected = r#"graph {
+    node_1
+    -1.5
+    "2nd"
+    "node"
+    "say \"hi\"
+there"
+    <<b>bold</b>>
+    "<a><b>"
+    ""
+}"#;
+    assert_eq!(graph.to_dot(), expected);
+}
+
+#[test]
+#[ignore]
+fn test_parse_graph() {
+    let graph: Graph = r#"
+        graph {
+            graph [bgcolor="yellow"]
+            a [color="red"]
+            b [color="blue"]
+            a -- b [color="green"]
+        }
+    "#
+    .parse()
+    .unwrap();
+
+    assert_eq!(graph.kind, GraphKind::Undirected);
+    assert_eq!(graph.attr("bgcolor"), Some("yellow"));
+    assert_eq!(
+        graph.nodes(),
+        vec![
+            Node::new("a").with_attrs(&[("color", "red")]),
+            Node::new("b").with_attrs(&[("color", "blue")]),
+        ]
+    );
+    assert_eq!(
+        graph.edges(),
+        vec![Edge::new("a", "b").with_attrs(&[("color", "green")])]
+    );
+}
+
+#[test]
+#[ignore]
+fn test_parse_edge_chains_and_implicit_nodes() {
+    let graph = Graph::from_dot("digraph G { a -> b -> c [style=dotted]; }").unwrap();
+
+    assert_eq!(graph.kind, GraphKind::Directed);
+    assert_eq!(graph.name.as_deref(), Some("G"));
+    assert_eq!(
+        graph.nodes(),
+        vec![Node::new("a"), Node::new("b"), Node::new("c")]
+    );
+    assert_eq!(
+        graph.edges(),
+        vec![
+            Edge::new("a", "b").with_attrs(&[("style", "dotted")]),
+            Edge::new("b", "c").with_attrs(&[("style", "dotted")]),
+        ]
+    );
+}
+
+#[test]
+#[ignore]
+fn test_parse_defaults_apply_to_later_statements() {
+    let graph = Graph::from_dot(
+        "digraph {
+            a
+            node [shape=box]; edge [color=red]
+            b [shape=circle]
+            a -> c
+        }",
+    )
+    .unwrap();
+
+    assert_eq!(graph.node_attr("a", "shape"), None);
+    assert_eq!(graph.node_attr("b", "shape"), Some("circle"));
+    assert_eq!(graph.node_attr("c", "shape"), Some("box"));
+    assert_eq!(graph.edge_attr(&graph.edges()[0], "color"), Some("red"));
+}
+
+#[test]
+#[ignore]
+fn test_parse_defaults_do_not_reach_earlier_statements() {
+    let graph = Graph::from_dot(
+        "digraph {
+            subgraph cluster_0 { a -> b; edge [color=red]; b -> c }
+        }",
+    )
+    .unwrap();
+
+    assert_eq!(graph.edge_attr(&graph.edges()[0], "color"), None);
+    assert_eq!(graph.edge_attr(&graph.edges()[1], "color"), Some("red"));
+    assert_eq!(Graph::from_dot(&graph.to_dot()).unwrap(), graph);
+}
+
+#[test]
+#[ignore]
+fn test_parse_subgraphs() {
+    let graph = Graph::from_dot(
+        "digraph {
+            subgraph cluster_0 { node [color=blue]; x; y }
+            z
+            a -> { b c }
+            { d e } -> f
+        }",
+    )
+    .unwrap();
+
+    assert_eq!(graph.node_attr("x", "color"), Some("blue"));
+    assert_eq!(graph.node_attr("z", "color"), None);
+    assert_eq!(
+        graph.edges(),
+        vec![
+            Edge::new("a", "b"),
+            Edge::new("a", "c"),
+            Edge::new("d", "f"),
+            Edge::new("e", "f"),
+        ]
+    );
+}
+
+#[test]
+#[ignore]
+fn test_parse_ids_and_comments() {
+    let graph = Graph::from_dot(
+        r#"# generated
+        /* a multi-line
+           comment */
+        strict graph "my graph" {
+            "node with spaces" -- -3.5 // trailing comment
+            "con" + "cat" [label=<<b>bold</b>>, tooltip="say \"hi\""]
+            "node with spaces" -- -3.5 [weight=2]
+            a:n -- b:p1:sw
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(graph.name.as_deref(), Some("my graph"));
+    let concat = graph.node("concat").unwrap();
+    assert_eq!(concat.attr("label"), Some("<<b>bold</b>>"));
+    assert_eq!(concat.attr("tooltip"), Some("say \"hi\""));
+    // The graph being strict, the repeated edge is merged into the first one.
+    assert_eq!(graph.edges().len(), 2);
+    assert_eq!(graph.edges()[0].attr("weight"), Some("2"));
+    assert_eq!(graph.edges()[1].attr("tailport"), Some("n"));
+    assert_eq!(graph.edges()[1].attr("headport"), Some("p1:sw"));
+}
+
+#[test]
+#[ignore]
+fn test_parse_round_trip() {
+    let graph = Graph::new()
+        .with_kind(GraphKind::Directed)
+        .with_nodes(&[
+            Node::new("a").with_attrs(&[("label", "A \"quoted\" name")]),
+            Node::new("node"),
+        ])
+        .with_edges(&[Edge::new("a", "node").with_attrs(&[("weight", "-1")])])
+        .with_attrs(&[("rankdir", "LR")]);
+
+    let parsed = Graph::from_dot(&graph.to_dot()).unwrap();
+
+    assert_eq!(parsed.nodes(), graph.nodes());
+    assert_eq!(parsed.edges(), graph.edges());
+    assert_eq!(parsed.attrs, graph.attrs);
+}
+
+#[test]
+#[ignore]
+fn test_escaped_ids_round_trip() {
+    let names = [
+        "back\\slash",
+        "trailing\\",
+        "double\\\\",
+        "before \\\"quote\"",
+        "line\nbreak",
+        "continued\\\nline",
+        "left\\l",
+    ];
+    let graph = Graph::new().with_nodes(&names.map(Node::new));
+
+    let parsed = Graph::from_dot(&graph.to_dot()).unwrap();
+
+    assert_eq!(parsed.nodes(), graph.nodes());
+}
+
+#[test]
+#[ignore]
+fn test_parse_errors_report_line_and_column() {
+    assert_eq!(
+        Graph::from_dot("graph {\n  a -> b\n}").unwrap_err(),
+        ParseError {
+            line: 2,
+            column: 5,
+            kind: ParseErrorKind::UnexpectedToken {
+                expected: "`--`",
+                found: "`->`".to_string()
+            }
+        }
+    );
+    assert_eq!(
+        Graph::from_dot("graph {\n  a [color=]\n}").unwrap_err(),
+        ParseError {
+            line: 2,
+            column: 12,
+            kind: ParseErrorKind::UnexpectedToken {
+                expected: "an ID",
+                found: "`]`".to_string()
+            }
+        }
+    );
+    assert_eq!(
+        Graph::from_dot("digraph {\n  a -> b").unwrap_err(),
+        ParseError {
+            line: 2,
+            column: 9,
+            kind: ParseErrorKind::UnexpectedEnd { expected: "`}`" }
+        }
+    );
+    assert_eq!(
+        Graph::from_dot("graph { a [label=\"open] }").unwrap_err(),
+        ParseError {
+            line: 1,
+            column: 18,
+            kind: ParseErrorKind::UnterminatedString
+        }
+    );
+    assert_eq!(
+        Graph::from_dot("graph { a ! b }").unwrap_err(),
+        ParseError {
+            line: 1,
+            column: 11,
+            kind: ParseErrorKind::UnexpectedChar('!')
+        }
+    );
+    assert_eq!(
+        Graph::from_dot("tree { }").unwrap_err().kind,
+        ParseErrorKind::UnexpectedToken {
+            expected: "`graph` or `digraph`",
+            found: "ID `tree`".to_string()
+        }
+    );
+}
+
+#[test]
+#[ignore]
+fn test_successors_and_predecessors() {
+    let graph = Graph::from_dot("digraph { a -> b; a -> c; c -> b; a -> b }").unwrap();
+
+    assert_eq!(graph.successors("a"), Some(vec!["b", "c"]));
+    assert_eq!(graph.predecessors("b"), Some(vec!["a", "c"]));
+    assert_eq!(graph.successors("b"), Some(vec![]));
+    assert_eq!(graph.successors("z"), None);
+}
+
+#[test]
+#[ignore]
+fn test_undirected_neighbours_go_both_ways() {
+    let graph = Graph::from_dot("graph { a -- b; c -- a }").unwrap();
+
+    assert_eq!(graph.successors("a"), Some(vec!["b", "c"]));
+    assert_eq!(graph.predecessors("b"), Some(vec!["a"]));
+}
+
+#[test]
+#[ignore]
+fn test_degree() {
+    let graph = Graph::from_dot("digraph { a -> b; a -> c; b -> a; a -> a }").unwrap();
+
+    assert_eq!(graph.out_degree("a"), Some(3));
+    assert_eq!(graph.in_degree("a"), Some(2));
+    assert_eq!(graph.degree("a"), Some(5));
+    assert_eq!(graph.degree("c"), Some(1));
+    assert_eq!(graph.degree("z"), None);
+}
+
+#[test]
+#[ignore]
+fn test_breadth_and_depth_first_traversal() {
+    let graph = Graph::from_dot("digraph { a -> b -> d; a -> c -> e; e -> a; f }").unwrap();
+
+    assert_eq!(graph.bfs("a"), Some(vec!["a", "b", "c", "d", "e"]));
+    assert_eq!(graph.dfs("a"), Some(vec!["a", "b", "d", "c", "e"]));
+    assert_eq!(graph.bfs("f"), Some(vec!["f"]));
+    assert_eq!(graph.dfs("z"), None);
+}
+
+#[test]
+#[ignore]
+fn test_topological_sort() {
+    let graph = Graph::from_dot("digraph { shirt -> tie -> jacket; trousers -> shoes; trousers -> belt -> jacket; socks -> shoes }").unwrap();
+
+    assert_eq!(
+        graph.topological_sort(),
+        Ok(vec![
+            "shirt", "tie", "trousers", "belt", "jacket", "socks", "shoes"
+        ])
+    );
+}
+
+#[test]
+#[ignore]
+fn test_topological_sort_reports_a_cycle() {
+    let graph = Graph::from_dot("digraph { start -> a -> b -> c -> a; c -> end }").unwrap();
+
+    assert_eq!(
+        graph.topological_sort(),
+        Err(AlgorithmError::Cycle(vec![
+            "a".to_string(),
+            "b".to_string(),
+            "c".to_string(),
+            "a".to_string()
+        ]))
+    );
+    assert_eq!(
+        Graph::from_dot("graph { a -- b }")
+            .unwrap()
+            .topological_sort(),
+        Err(AlgorithmError::NotDirected)
+    );
+}
+
+#[test]
+#[ignore]
+fn test_connected_components() {
+    let graph =
+        Graph::from_dot("digraph { a -> b -> c -> a; c -> d; d -> e -> d; f -> g; h }").unwrap();
+
+    assert_eq!(
+        graph.strongly_connected_components(),
+        vec![
+            vec!["a", "b", "c"],
+            vec!["d", "e"],
+            vec!["f"],
+            vec!["g"],
+            vec!["h"]
+        ]
+    );
+    assert_eq!(
+        graph.weakly_connected_components(),
+        vec![vec!["a", "b", "c", "d", "e"], vec!["f", "g"], vec!["h"]]
+    );
+}
+
+#[test]
+#[ignore]
+fn test_strongly_connected_components_of_a_long_cycle() {
+    let names: Vec<String> = (0..5_000).map(|idx| format!("n{idx}")).collect();
+    let edges: Vec<Edge> = names
+        .iter()
+        .zip(names.iter().cycle().skip(1))
+        .map(|(from, to)| Edge::new(from, to))
+        .collect();
+    let graph = Graph::new()
+        .with_kind(GraphKind::Directed)
+        .with_edges(&edges);
+
+    let components = graph.strongly_connected_components();
+
+    assert_eq!(components.len(), 1);
+    assert_eq!(components[0].len(), names.len());
+}
+
+#[test]
+#[ignore]
+fn test_weighted_shortest_path() {
+    let graph = Graph::from_dot(
+        "digraph { a -> b [weight=4]; a -> c [weight=1]; c -> b [weight=1.5]; b -> d; x }",
+    )
+    .unwrap();
+
+    assert_eq!(
+        graph.shortest_path("a", "d", "weight"),
+        Ok(Some(Path {
+            cost: 3.5,
+            nodes: vec![
+                "a".to_string(),
+                "c".to_string(),
+                "b".to_string(),
+                "d".to_string()
+            ]
+        }))
+    );
+    assert_eq!(graph.shortest_path("a", "x", "weight"), Ok(None));
+    assert_eq!(
+        graph.shortest_path("a", "y", "weight"),
+        Err(AlgorithmError::UnknownNode("y".to_string()))
+    );
+}
+
+#[test]
+#[ignore]
+fn test_shortest_path_uses_default_weights() {
+    let graph = Graph::from_dot(
+        "digraph { edge [weight=5]; a -> b; a -> c [weight=1]; subgraph { edge [weight=2]; c -> b } }",
+    )
+    .unwrap();
+
+    assert_eq!(
+        graph.shortest_path("a", "b", "weight"),
+        Ok(Some(Path {
+            cost: 3.0,
+            nodes: vec!["a".to_string(), "c".to_string(), "b".to_string()]
+        }))
+    );
+}
+
+#[test]
+#[ignore]
+fn test_shortest_path_rejects_bad_weights() {
+    let graph = Graph::from_dot("digraph { a -> b [cost=heavy] }").unwrap();
+
+    assert_eq!(
+        graph.shortest_path("a", "b", "cost"),
+        Err(AlgorithmError::InvalidWeight {
+            from: "a".to_string(),
+            to: "b".to_string(),
+            value: "heavy".to_string()
+        })
+    );
+}
+
+#[test]
+#[ignore]
+fn test_node_lookup_returns_a_reference() {
+    let graph = Graph::new().with_nodes(&[Node::new("a"), Node::new("b")]);
+
+    assert!(std::ptr::eq(graph.node("b").unwrap(), &graph.nodes()[1]));
+    assert!(graph.contains_node("a"));
+    assert!(!graph.contains_node("c"));
+}
+
+#[test]
+#[ignore]
+fn test_repeated_nodes_are_merged() {
+    let graph = Graph::new().with_nodes(&[
+        Node::new("a").with_attrs(&[("color", "red"), ("shape", "box")]),
+        Node::new("b"),
+        Node::new("a").with_attrs(&[("color", "blue")]),
+    ]);
+
+    assert_eq!(
+        graph.nodes(),
+        [
+            Node::new("a").with_attrs(&[("color", "blue"), ("shape", "box")]),
+            Node::new("b")
+        ]
+    );
+}
+
+#[test]
+#[ignore]
+fn test_fallible_builder_validates_nodes_and_edges() {
+    let nodes = [Node::new("a"), Node::new("b")];
+
+    let graph = Graph::new()
+        .try_with_nodes(&nodes)
+        .and_then(|graph| graph.try_with_edges(&[Edge::new("a", "b")]))
+        .unwrap();
+    assert_eq!(graph.edges(), [Edge::new("a", "b")]);
+
+    assert_eq!(
+        Graph::new()
+            .try_with_nodes(&nodes)
+            .and_then(|graph| graph.try_with_edges(&[Edge::new("a", "c")])),
+        Err(GraphError::UnknownNode("c".to_string()))
+    );
+    assert_eq!(
+        Graph::new().try_with_nodes(&[Node::new("a"), Node::new("a")]),
+        Err(GraphError::DuplicateNode("a".to_string()))
+    );
+}
+
+#[test]
+#[ignore]
+fn test_add_nodes_and_edges() {
+    let mut graph = Graph::new();
+
+    assert_eq!(graph.add_node(Node::new("a")), Ok(()));
+    assert_eq!(
+        graph.add_node(Node::new("a")),
+        Err(GraphError::DuplicateNode("a".to_string()))
+    );
+    assert_eq!(
+        graph.add_edge(Edge::new("a", "b")),
+        Err(GraphError::UnknownNode("b".to_string()))
+    );
+    assert_eq!(graph.add_node(Node::new("b")), Ok(()));
+    assert_eq!(graph.add_edge(Edge::new("a", "b")), Ok(()));
+    assert_eq!(graph.edges(), [Edge::new("a", "b")]);
+}
+
+#[test]
+#[ignore]
+fn test_remove_node_drops_its_edges() {
+    let mut graph = Graph::from_dot("graph { a -- b -- c; c -- a; d }").unwrap();
+
+    assert_eq!(graph.remove_node("b"), Some(Node::new("b")));
+    assert_eq!(graph.remove_node("b"), None);
+    assert_eq!(
+        graph.nodes(),
+        [Node::new("a"), Node::new("c"), Node::new("d")]
+    );
+    assert_eq!(graph.edges(), [Edge::new("c", "a")]);
+    assert_eq!(graph.node("d"), Some(&Node::new("d")));
+}
+
+#[test]
+#[ignore]
+fn test_rename_node_updates_its_edges() {
+    let mut graph = Graph::from_dot("digraph { a -> b -> a; c }").unwrap();
+
+    assert_eq!(graph.rename_node("a", "z"), Ok(()));
+    assert_eq!(
+        graph.nodes(),
+        [Node::new("z"), Node::new("b"), Node::new("c")]
+    );
+    assert_eq!(graph.edges(), [Edge::new("z", "b"), Edge::new("b", "z")]);
+    assert!(graph.node("a").is_none());
+    assert_eq!(
+        graph.rename_node("b", "c"),
+        Err(GraphError::DuplicateNode("c".to_string()))
+    );
+    assert_eq!(
+        graph.rename_node("a", "y"),
+        Err(GraphError::UnknownNode("a".to_string()))
+    );
+    assert_eq!(graph.rename_node("b", "b"), Ok(()));
+    assert_eq!(graph.edges(), [Edge::new("z", "b"), Edge::new("b", "z")]);
+}
+
+#[test]
+#[ignore]
+fn test_nodes_edited_directly_are_still_found() {
+    let mut graph = Graph::new().with_nodes(&[Node::new("a"), Node::new("b")]);
+
+    graph.nodes.remove(0);
+    graph.nodes.push(Node::new("c"));
+
+    assert!(graph.node("a").is_none());
+    assert_eq!(graph.node("b"), Some(&Node::new("b")));
+    assert_eq!(graph.node("c"), Some(&Node::new("c")));
+    assert_eq!(graph.add_edge(Edge::new("b", "c")), Ok(()));
+    assert_eq!(
+        graph.add_node(Node::new("c")),
+        Err(GraphError::DuplicateNode("c".to_string()))
+    );
+    assert_eq!(graph.remove_node("b"), Some(Node::new("b")));
+    assert_eq!(graph.nodes, [Node::new("c")]);
+}
+
+#[test]
+#[ignore]
+fn test_strict_graph_merges_repeated_edges() {
+    let graph = Graph::new()
+        .with_strict(true)
+        .with_nodes(&[Node::new("a"), Node::new("b")])
+        .with_edges(&[
+            Edge::new("a", "b").with_attrs(&[("color", "red")]),
+            Edge::new("b", "a").with_attrs(&[("style", "bold")]),
+        ]);
+
+    assert_eq!(
+        graph.edges(),
+        [Edge::new("a", "b").with_attrs(&[("color", "red"), ("style", "bold")])]
+    );
+    assert_eq!(
+        graph.to_dot(),
+        "strict graph {\n    a\n    b\n    a -- b [color=red, style=bold]\n}"
+    );
+}
+
+#[test]
+#[ignore]
+fn test_subgraphs_and_clusters() {
+    let graph = Graph::new().with_subgraphs(&[
+        Subgraph::new("cluster_front")
+            .with_attrs(&[("label", "Front end")])
+            .with_nodes(&["ui"])
+            .with_subgraphs(&[Subgraph::new("widgets").with_nodes(&["button"])]),
+        Subgraph::anonymous().with_nodes(&["db"]),
+    ]);
+
+    assert_eq!(
+        graph.nodes(),
+        [Node::new("ui"), Node::new("button"), Node::new("db")]
+    );
+    let cluster = graph.subgraph("cluster_front").unwrap();
+    assert!(cluster.is_cluster());
+    assert_eq!(cluster.attr("label"), Some("Front end"));
+    assert!(cluster.contains("button"));
+    assert!(!cluster.contains("db"));
+    assert!(!graph.subgraph("widgets").unwrap().is_cluster());
+    assert!(graph.subgraph("backend").is_none());
+}
+
+#[test]
+#[ignore]
+fn test_attributes_are_inherited_through_the_graph() {
+    let graph = Graph::new()
+        .with_kind(GraphKind::Directed)
+        .with_node_attrs(&[("shape", "box"), ("color", "black")])
+        .with_edge_attrs(&[("style", "dashed")])
+        .with_nodes(&[Node::new("a").with_attrs(&[("color", "red")])])
+        .with_subgraphs(&[Subgraph::new("cluster_0")
+            .with_node_attrs(&[("color", "blue")])
+            .with_edge_attrs(&[("style", "bold")])
+            .with_nodes(&["b", "c"])
+            .with_subgraphs(&[Subgraph::new("inner")
+                .with_node_attrs(&[("color", "green")])
+                .with_nodes(&["d"])])])
+        .with_edges(&[Edge::new("b", "d"), Edge::new("a", "b")]);
+
+    assert_eq!(graph.node_attr("a", "color"), Some("red"));
+    assert_eq!(graph.node_attr("b", "color"), Some("blue"));
+    assert_eq!(graph.node_attr("d", "color"), Some("green"));
+    assert_eq!(graph.node_attr("d", "shape"), Some("box"));
+    assert_eq!(graph.node_attr("d", "label"), None);
+    assert_eq!(graph.node_attr("z", "color"), None);
+    assert_eq!(graph.edge_attr(&graph.edges()[0], "style"), Some("bold"));
+    assert_eq!(graph.edge_attr(&graph.edges()[1], "style"), Some("dashed"));
+}
+
+#[test]
+#[ignore]
+fn test_removing_and_renaming_nodes_updates_subgraphs() {
+    let mut graph = Graph::new().with_subgraphs(&[Subgraph::new("cluster_0")
+        .with_nodes(&["a", "b"])
+        .with_subgraphs(&[Subgraph::new("inner").with_nodes(&["c"])])]);
+
+    graph.remove_node("a");
+    graph.rename_node("c", "d").unwrap();
+
+    let cluster = graph.subgraph("cluster_0").unwrap();
+    assert_eq!(cluster.nodes, vec!["b".to_string()]);
+    assert_eq!(cluster.subgraphs[0].nodes, vec!["d".to_string()]);
+}
+
+#[test]
+#[ignore]
+fn test_subgraphs_to_dot() {
+    let graph = Graph::new()
+        .with_kind(GraphKind::Directed)
+        .with_node_attrs(&[("shape", "box")])
+        .with_subgraphs(&[Subgraph::new("cluster_0")
+            .with_attrs(&[("label", "Zero")])
+            .with_edge_attrs(&[("color", "red")])
+            .with_nodes(&["a"])
+            .with_subgraphs(&[Subgraph::anonymous().with_nodes(&["b"])])])
+        .with_edges(&[Edge::new("a", "b")]);
+
+    let expected = "digraph {
+    node [shape=box]
+    subgraph cluster_0 {
+        graph [label=Zero]
+        edge [color=red]
+        subgraph {
+            b
+        }
+        a
+        a -> b
+    }
+    a
+    b
+}";
+    assert_eq!(graph.to_dot(), expected);
+}
+
+#[test]
+#[ignore]
+fn test_parse_keeps_subgraphs() {
+    let graph = Graph::from_dot(
+        "strict digraph {
+            subgraph cluster_0 { label=\"Zero\"; a; subgraph inner { b } }
+            { c d } -> e
+            subgraph cluster_0 { f }
+            subgraph { graph [rank=same]; g }
+        }",
+    )
+    .unwrap();
+
+    assert!(graph.strict);
+    assert_eq!(graph.subgraphs().len(), 2);
+    let cluster = &graph.subgraphs()[0];
+    assert_eq!(cluster.name.as_deref(), Some("cluster_0"));
+    assert_eq!(cluster.attr("label"), Some("Zero"));
+    assert_eq!(cluster.nodes, vec!["a".to_string(), "f".to_string()]);
+    assert_eq!(
+        cluster.subgraphs,
+        vec![Subgraph::new("inner").with_nodes(&["b"])]
+    );
+    assert_eq!(
+        graph.subgraphs()[1],
+        Subgraph::anonymous()
+            .with_attrs(&[("rank", "same")])
+            .with_nodes(&["g"])
+    );
+}
+
+#[test]
+#[ignore]
+fn test_graph_macro() {
+    let graph = graph! {
+        graph [rankdir = "LR"];
+        node [shape = "box"];
+        edge [color = "grey"];
+        a [color = "red", label = "A"];
+        a -> b -> c [style = "dotted"];
+        "d e" -> a;
+    };
+
+    let expected = Graph::new()
+        .with_kind(GraphKind::Directed)
+        .with_attrs(&[("rankdir", "LR")])
+        .with_node_attrs(&[("shape", "box")])
+        .with_edge_attrs(&[("color", "grey")])
+        .with_nodes(&[
+            Node::new("a").with_attrs(&[("color", "red"), ("label", "A")]),
+            Node::new("b"),
+            Node::new("c"),
+            Node::new("d e"),
+        ])
+        .with_edges(&[
+            Edge::new("a", "b").with_attrs(&[("style", "dotted")]),
+            Edge::new("b", "c").with_attrs(&[("style", "dotted")]),
+            Edge::new("d e", "a"),
+        ]);
+    assert_eq!(graph, expected);
+}
+
+#[test]
+#[ignore]
+fn test_graph_macro_undirected_with_expressions() {
+    let width = 2;
+    let graph = graph! {
+        x -- y -- 3 [penwidth = width * 2,];
+        y;
+    };
+
+    assert_eq!(graph.kind, GraphKind::Undirected);
+    assert_eq!(
+        graph.nodes(),
+        [Node::new("x"), Node::new("y"), Node::new("3")]
+    );
+    assert_eq!(
+        graph.edges()[1],
+        Edge::new("y", "3").with_attrs(&[("penwidth", "4")])
+    );
+    assert_eq!(graph! {}, Graph::new());
+}
+
+#[test]
+#[ignore]
+fn test_graph_macro_applies_defaults_in_statement_order() {
+    let graph = graph! {
+        a -> b;
+        node [shape = "box"];
+        edge [color = "red"];
+        b -> c;
+        node [shape = "circle"];
+        d;
+    };
+
+    let expected = Graph::from_dot(
+        "digraph {
+            a -> b
+            node [shape=box]
+            edge [color=red]
+            b -> c
+            node [shape=circle]
+            d
+        }",
+    )
+    .unwrap();
+    assert_eq!(graph, expected);
+    assert_eq!(graph.node_attr("a", "shape"), None);
+    assert_eq!(graph.node_attr("c", "shape"), Some("box"));
+    assert_eq!(graph.node_attr("d", "shape"), Some("circle"));
+    assert_eq!(graph.edge_attr(&graph.edges()[0], "color"), None);
+    assert_eq!(graph.edge_attr(&graph.edges()[1], "color"), Some("red"));
+}
+
+#[test]
+#[ignore]
+fn test_graph_macro_compile_fails_missing_semicolon() {
+    simple_trybuild::compile_fail("missing-semicolon.rs");
+}
+
+#[test]
+#[ignore]
+fn test_graph_macro_compile_fails_mixed_edges() {
+    simple_trybuild::compile_fail("mixed-edges.rs");
+}
+
+#[test]
+#[ignore]
+fn test_graph_macro_compile_fails_dangling_edge() {
+    simple_trybuild::compile_fail("dangling-edge.rs");
+}
+
+#[test]
+#[ignore]
+fn test_graph_macro_compile_fails_malformed_attrs() {
+    simple_trybuild::compile_fail("malformed-attrs.rs");
+}
+
+#[test]
+#[ignore]
+fn test_graph_macro_compile_fails_invalid_node_name() {
+    simple_trybuild::compile_fail("invalid-node-name.rs");
+}
+
+mod simple_trybuild {
+    use std::path::PathBuf;
+    use std::process::Command;
+
+    pub fn compile_fail(file_name: &str) {
+        let invalid_path: PathBuf = ["tests", "invalid"].iter().collect::<PathBuf>();
+
+        let mut file_path = invalid_path.clone();
+        file_path.push(file_name);
+        assert!(file_path.exists(), "{:?} does not exist.", file_path);
+
+        let test_name = file_name.replace('.', "-");
+        let target_dir = ["..", "..", "target", "tests", "dot-dsl"]
+            .iter()
+            .collect::<PathBuf>();
+
+        let result = Command::new("cargo")
+            .current_dir(invalid_path)
+            .arg("build")
+            .arg("--offline")
+            .arg("--target-dir")
+            .arg(target_dir)
+            .arg("--bin")
+            .arg(test_name)
+            .output();
+
+        if let Ok(result) = result {
+            assert!(
+                !result.status.success(),
+                "Expected {:?} to fail to compile, but it succeeded.",
+                file_path
+            );
+            expect_stderr(&file_path.with_extension("stderr"), &result.stderr);
+        } else {
+            panic!("Running subprocess failed.");
+        }
+    }
+
+    // Every line of the `.stderr` file next to the fixture must be found, in order, in
+    // the compiler output. Only the lines that matter are kept in the file, so that
+    // the notes and spans of a new compiler do not break the test.
+    fn expect_stderr(expected_path: &PathBuf, stderr: &[u8]) {
+        let expected = std::fs::read_to_string(expected_path)
+            .unwrap_or_else(|_| panic!("{:?} does not exist.", expected_path));
+        let stderr = String::from_utf8_lossy(stderr);
+        let mut actual = stderr.lines();
+        for line in expected
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+        {
+            assert!(
+                actual.any(|actual| actual.contains(line)),
+                "Expected {:?} in the compiler output:\n{}",
+                line,
+                stderr
+            );
+        }
+    }
+}
+
+fn assert_no_overlap(layout: &Layout) {
+    for (i, a) in layout.nodes.iter().enumerate() {
+        for b in &layout.nodes[i + 1..] {
+            let apart_x = (a.center.x - b.center.x).abs() >= (a.width + b.width) / 2.0;
+            let apart_y = (a.center.y - b.center.y).abs() >= (a.height + b.height) / 2.0;
+            assert!(apart_x || apart_y, "{} overlaps {}", a.name, b.name);
+        }
+        assert!(a.center.x - a.width / 2.0 >= 0.0 && a.center.x + a.width / 2.0 <= layout.width);
+        assert!(a.center.y - a.height / 2.0 >= 0.0 && a.center.y + a.height / 2.0 <= layout.height);
+    }
+}
+
+#[test]
+#[ignore]
+fn test_layout_ranks_follow_edges() {
+    let graph = Graph::from_dot("digraph { a -> b -> c; a -> c; c -> d; e }").unwrap();
+    let layout = graph.layout();
+
+    let rank = |name| layout.node(name).unwrap().rank;
+    assert_eq!(["a", "b", "c", "d", "e"].map(rank), [0, 1, 2, 3, 0]);
+    let y = |name| layout.node(name).unwrap().center.y;
+    assert!(y("a") < y("b") && y("b") < y("c") && y("c") < y("d"));
+    assert_eq!(y("a"), y("e"));
+    assert_no_overlap(&layout);
+}
+
+#[test]
+#[ignore]
+fn test_layout_breaks_cycles_and_routes_long_edges() {
+    let graph = Graph::from_dot("digraph { a -> b -> c -> a; a -> c; c -> c }").unwrap();
+    let layout = graph.layout();
+
+    assert_eq!(layout.edges.len(), 5);
+    // The edge closing the cycle is drawn upwards, from `c` back to `a`.
+    let back = &layout.edges[2];
+    assert_eq!((back.from.as_str(), back.to.as_str()), ("c", "a"));
+    assert!(back.points[0].y > back.points.last().unwrap().y);
+    // Edges skipping a rank bend around the node in between.
+    assert_eq!(layout.edges[3].points.len(), 3);
+    assert_eq!(layout.edges[4].points.len(), 4);
+    for edge in &layout.edges {
+        let from = layout.node(&edge.from).unwrap();
+        let start = edge.points[0];
+        // Ends are on the outline of the nodes, not at their center.
+        assert!(start != from.center);
+        assert!((start.x - from.center.x).abs() <= from.width / 2.0 + 1e-9);
+        assert!((start.y - from.center.y).abs() <= from.height / 2.0 + 1e-9);
+    }
+    assert_no_overlap(&layout);
+}
+
+#[test]
+#[ignore]
+fn test_layout_orders_ranks_to_avoid_crossings() {
+    let graph = Graph::from_dot("digraph { a -> d; b -> c; a; b; c; d }").unwrap();
+    let layout = graph.layout();
+
+    let x = |name| layout.node(name).unwrap().center.x;
+    assert_eq!(x("a") < x("b"), x("d") < x("c"));
+}
+
+#[test]
+#[ignore]
+fn test_layout_left_to_right_and_sizes() {
+    let graph = Graph::from_dot(
+        "digraph { rankdir=LR; a [label=\"a much longer label\"]; b [shape=circle]; a -> b; c [width=2] }",
+    )
+    .unwrap();
+    let layout = graph.layout();
+
+    let a = layout.node("a").unwrap();
+    let b = layout.node("b").unwrap();
+    assert!(a.center.x < b.center.x);
+    assert!(a.width > b.width);
+    assert_eq!(b.width, b.height);
+    assert!(layout.node("c").unwrap().width >= 144.0);
+    assert_no_overlap(&layout);
+    assert!(Graph::new().layout().nodes.is_empty());
+}
+
+#[test]
+#[ignore]
+fn test_svg_rendering() {
+    let graph = Graph::from_dot(
+        r#"digraph "deps & more" {
+            label="Dependencies"
+            app [shape=box, style="filled,rounded", fillcolor=yellow, label="App <main>"]
+            db [shape=diamond, color=blue]
+            hidden [style=invis]
+            app -> db [label=queries, style=dashed, color=red]
+            app -> lib [style=bold]
+        }"#,
+    )
+    .unwrap();
+    let svg = graph.to_svg();
+
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(svg.ends_with("</svg>\n"));
+    assert!(svg.contains("<title>deps &amp; more</title>"));
+    assert!(svg.contains(">App &lt;main&gt;</text>"));
+    assert!(svg.contains("rx=\"6\" ry=\"6\" fill=\"yellow\" stroke=\"black\""));
+    assert!(svg.contains("<polygon points=") && svg.contains("stroke=\"blue\""));
+    assert!(svg.contains("<ellipse") && svg.contains(">lib</text>"));
+    assert!(!svg.contains("<title>hidden</title>"));
+    assert!(svg.contains("<title>app-&gt;db</title>"));
+    assert!(svg.contains("fill=\"none\" stroke=\"red\" stroke-dasharray=\"5,2\""));
+    assert!(svg.contains("stroke-width=\"2\""));
+    assert!(svg.contains(">queries</text>"));
+    assert!(svg.contains(">Dependencies</text>"));
+    // Every directed edge gets an arrow head.
+    assert_eq!(svg.matches("<polyline").count(), 2);
+    assert_eq!(svg.matches("<polygon").count(), 3);
+}
+
+#[test]
+#[ignore]
+fn test_typed_node_attributes() {
+    let mut node = Node::new("a");
+    node.set_color(Color::Named("red".to_string())).unwrap();
+    node.set_fillcolor(Color::Rgb(0x12, 0xab, 0xff)).unwrap();
+    node.set_shape(Shape::DoubleCircle).unwrap();
+    node.set_style(&[Style::Filled, Style::Dashed]).unwrap();
+    node.set_width(1.5).unwrap();
+
+    assert_eq!(node.attr("fillcolor"), Some("#12abff"));
+    assert_eq!(node.attr("shape"), Some("doublecircle"));
+    assert_eq!(node.attr("style"), Some("filled,dashed"));
+    assert_eq!(
+        node.color(),
+        Ok(Some(Color::Named("red".to_string()).into()))
+    );
+    assert_eq!(
+        node.fillcolor(),
+        Ok(Some(ColorList(vec![(Color::Rgb(0x12, 0xab, 0xff), None)])))
+    );
+    assert_eq!(node.shape(), Ok(Some(Shape::DoubleCircle)));
+    assert_eq!(node.style(), Ok(vec![Style::Filled, Style::Dashed]));
+    assert_eq!(node.width(), Ok(Some(1.5)));
+    assert_eq!(node.height(), Ok(None));
+    assert_eq!(
+        node.set_width(0.0),
+        Err(AttrError::InvalidValue {
+            key: "width".to_string(),
+            value: "0".to_string()
+        })
+    );
+}
+
+#[test]
+#[ignore]
+fn test_invalid_attribute_values_are_reported() {
+    let node = Node::new("a").with_attrs(&[("shape", "blob"), ("color", "#12345")]);
+
+    assert_eq!(
+        node.shape(),
+        Err(AttrError::InvalidValue {
+            key: "shape".to_string(),
+            value: "blob".to_string()
+        })
+    );
+    assert_eq!(
+        node.validate(),
+        Err(AttrError::InvalidValue {
+            key: "color".to_string(),
+            value: "#12345".to_string()
+        })
+    );
+    assert_eq!(
+        Edge::new("a", "b")
+            .with_attrs(&[("style", "filled")])
+            .style(),
+        Err(AttrError::InvalidValue {
+            key: "style".to_string(),
+            value: "filled".to_string()
+        })
+    );
+}
+
+#[test]
+#[ignore]
+fn test_unknown_attributes_are_caught_but_kept() {
+    let mut node = Node::new("a").with_attrs(&[("colr", "red")]);
+
+    assert_eq!(
+        node.validate(),
+        Err(AttrError::UnknownAttribute("colr".to_string()))
+    );
+    assert_eq!(node.attr("colr"), Some("red"));
+    assert_eq!(
+        node.set_attr("weight", "2"),
+        Err(AttrError::UnknownAttribute("weight".to_string()))
+    );
+    assert_eq!(node.set_attr("tooltip", "hello"), Ok(()));
+    assert_eq!(
+        Edge::new("a", "b").try_with_attrs(&[("weight", "2"), ("arrowsize", "-1")]),
+        Err(AttrError::InvalidValue {
+            key: "arrowsize".to_string(),
+            value: "-1".to_string()
+        })
+    );
+}
+
+#[test]
+#[ignore]
+fn test_colors() {
+    let parse = |color: &str| color.parse::<Color>();
+
+    assert_eq!(parse("Navy"), Ok(Color::Named("Navy".to_string())));
+    assert_eq!(parse("grey75"), Ok(Color::Named("grey75".to_string())));
+    assert_eq!(parse("orange3"), Ok(Color::Named("orange3".to_string())));
+    // Names of the X11 scheme that SVG does not know.
+    assert_eq!(parse("navyblue"), Ok(Color::Named("navyblue".to_string())));
+    assert_eq!(
+        parse("LightGoldenrod2"),
+        Ok(Color::Named("LightGoldenrod2".to_string()))
+    );
+    assert!(Node::new("a")
+        .try_with_attrs(&[("color", "violetred"), ("fillcolor", "none")])
+        .is_ok());
+    assert!(parse("none1").is_err());
+    assert_eq!(
+        parse("/accent3/2"),
+        Ok(Color::Named("/accent3/2".to_string()))
+    );
+    assert_eq!(parse("#ff000080"), Ok(Color::Rgba(255, 0, 0, 128)));
+    assert_eq!(parse("0.5, 0.25 1"), Ok(Color::Hsv(0.5, 0.25, 1.0)));
+    assert!(parse("redd").is_err());
+    assert!(parse("orange7").is_err());
+    assert!(parse("grey101").is_err());
+    assert!(parse("#ggg000").is_err());
+    assert!(parse("1.5 0 0").is_err());
+    assert_eq!(Color::Hsv(0.5, 0.25, 1.0).to_string(), "0.5 0.25 1");
+
+    let mut edge = Edge::new("a", "b").with_attrs(&[("color", "red;0.3:blue")]);
+    assert_eq!(edge.validate(), Ok(()));
+    let colors = edge.color().unwrap().unwrap();
+    assert_eq!(
+        colors,
+        ColorList(vec![
+            (Color::Named("red".to_string()), Some(0.3)),
+            (Color::Named("blue".to_string()), None),
+        ])
+    );
+    assert_eq!(colors.first(), Some(&Color::Named("red".to_string())));
+    edge.set_color(colors).unwrap();
+    assert_eq!(edge.attr("color"), Some("red;0.3:blue"));
+    assert!("red;1.5".parse::<ColorList>().is_err());
+}
+
+#[test]
+#[ignore]
+fn test_less_common_attributes_are_known() {
+    let node = Node::new("a").with_attrs(&[("xlp", "1,2"), ("vertices", "0,0 1,1"), ("z", "3")]);
+    assert_eq!(node.validate(), Ok(()));
+    let graph = Graph::new().with_attrs(&[("stylesheet", "style.css"), ("TBbalance", "min")]);
+    assert_eq!(graph.validate(), Ok(()));
+    let edge = Edge::new("a", "b").with_attrs(&[("tailURL", "x.html"), ("head_lp", "1,2")]);
+    assert_eq!(edge.validate(), Ok(()));
+}
+
+#[test]
+#[ignore]
+fn test_typed_graph_attributes_and_validate_all() {
+    let mut graph = Graph::from_dot(
+        "digraph { rankdir=LR; node [shape=box]; subgraph cluster_0 { rank=same; a } a -> b [weight=3] }",
+    )
+    .unwrap();
+
+    assert_eq!(graph.rankdir(), Ok(Some(RankDir::LeftToRight)));
+    assert_eq!(graph.edges()[0].weight(), Ok(Some(3.0)));
+    assert_eq!(graph.validate_all(), Ok(()));
+    graph.set_rankdir(RankDir::BottomToTop).unwrap();
+    assert_eq!(graph.attr("rankdir"), Some("BT"));
+    assert_eq!(
+        graph.set_attr("rank", "same"),
+        Err(AttrError::UnknownAttribute("rank".to_string()))
+    );
+
+    let graph = graph.with_edge_attrs(&[("arrowhead", "vee"), ("colour", "red")]);
+    assert_eq!(
+        graph.validate_all(),
+        Err(AttrError::UnknownAttribute("colour".to_string()))
+    );
+}
+
+#[test]
+#[ignore]
+fn test_diff_reports_kind_strictness_and_subgraphs() {
+    let old = Graph::from_dot(
+        "graph { subgraph cluster_0 { a } subgraph cluster_1 { b } { rank=same; c } }",
+    )
+    .unwrap();
+    let new = Graph::from_dot(
+        "strict digraph g { subgraph cluster_0 { a; d } { rank=same; c } subgraph cluster_2 { e } }",
+    )
+    .unwrap();
+
+    let diff = old.diff(&new);
+    assert_eq!(
+        diff.kind,
+        Some(Change {
+            old: GraphKind::Undirected,
+            new: GraphKind::Directed
+        })
+    );
+    assert_eq!(
+        diff.strict,
+        Some(Change {
+            old: false,
+            new: true
+        })
+    );
+    assert_eq!(
+        diff.name,
+        Some(Change {
+            old: None,
+            new: Some("g".to_string())
+        })
+    );
+    assert_eq!(
+        diff.changed_subgraphs,
+        vec![Change {
+            old: Subgraph::new("cluster_0").with_nodes(&["a"]),
+            new: Subgraph::new("cluster_0").with_nodes(&["a", "d"]),
+        }]
+    );
+    assert_eq!(
+        diff.removed_subgraphs,
+        vec![Subgraph::new("cluster_1").with_nodes(&["b"])]
+    );
+    assert_eq!(
+        diff.added_subgraphs,
+        vec![Subgraph::new("cluster_2").with_nodes(&["e"])]
+    );
+}
+
+#[test]
+#[ignore]
+fn test_diff_of_identical_graphs_is_empty() {
+    let graph = Graph::from_dot("graph { a [color=red]; a -- b -- c }").unwrap();
+
+    assert!(graph.diff(&graph.clone()).is_empty());
+    // The ends of undirected edges may be swapped.
+    let swapped = Graph::from_dot("graph { a [color=red]; b -- a; c -- b }").unwrap();
+    assert!(graph.diff(&swapped).is_empty());
+}
+
+#[test]
+#[ignore]
+fn test_diff_reports_changes() {
+    let old = Graph::from_dot(
+        "digraph { rankdir=LR; a [color=red, shape=box]; b; c; a -> b [weight=1]; b -> c; b -> c }",
+    )
+    .unwrap();
+    let new = Graph::from_dot(
+        "digraph { rankdir=TB; label=v2; a [color=blue, shape=box]; b; d; a -> b [weight=2]; b -> d; b -> d }",
+    )
+    .unwrap();
+
+    let change = |key: &str, old: Option<&str>, new: Option<&str>| AttrChange {
+        key: key.to_string(),
+        old: old.map(|v| v.to_string()),
+        new: new.map(|v| v.to_string()),
+    };
+    assert_eq!(
+        old.diff(&new),
+        GraphDiff {
+            attrs: vec![
+                change("label", None, Some("v2")),
+                change("rankdir", Some("LR"), Some("TB")),
+            ],
+            added_nodes: vec![Node::new("d")],
+            removed_nodes: vec![Node::new("c")],
+            changed_nodes: vec![NodeChange {
+                name: "a".to_string(),
+                attrs: vec![change("color", Some("red"), Some("blue"))],
+            }],
+            added_edges: vec![Edge::new("b", "d"), Edge::new("b", "d")],
+            removed_edges: vec![Edge::new("b", "c"), Edge::new("b", "c")],
+            changed_edges: vec![EdgeChange {
+                from: "a".to_string(),
+                to: "b".to_string(),
+                attrs: vec![change("weight", Some("1"), Some("2"))],
+            }],
+            ..GraphDiff::default()
+        }
+    );
+}
+
+#[test]
+#[ignore]
+fn test_merge_combines_graphs() {
+    let ours =
+        Graph::from_dot("digraph { a [color=red]; a -> b; subgraph cluster_0 { label=Zero; a } }")
+            .unwrap();
+    let theirs = Graph::from_dot(
+        "digraph { a [shape=box]; a -> b [weight=2]; b -> c; subgraph cluster_0 { c } }",
+    )
+    .unwrap();
+
+    let merged = ours.merge(&theirs, MergePolicy::Fail).unwrap();
+    assert_eq!(
+        merged.nodes(),
+        [
+            Node::new("a").with_attrs(&[("color", "red"), ("shape", "box")]),
+            Node::new("b"),
+            Node::new("c"),
+        ]
+    );
+    assert_eq!(
+        merged.edges(),
+        [
+            Edge::new("a", "b").with_attrs(&[("weight", "2")]),
+            Edge::new("b", "c"),
+        ]
+    );
+    let cluster = merged.subgraph("cluster_0").unwrap();
+    assert_eq!(cluster.nodes, vec!["a".to_string(), "c".to_string()]);
+    assert_eq!(cluster.attr("label"), Some("Zero"));
+}
+
+#[test]
+#[ignore]
+fn test_merge_conflict_policies() {
+    let ours = Graph::from_dot("graph { a [color=red]; a -- b [style=bold] }").unwrap();
+    let theirs = Graph::from_dot("graph { a [color=blue]; b -- a [style=dashed] }").unwrap();
+
+    assert_eq!(
+        ours.merge(&theirs, MergePolicy::Fail),
+        Err(MergeConflict {
+            item: ConflictItem::Node("a".to_string()),
+            key: "color".to_string(),
+            ours: "red".to_string(),
+            theirs: "blue".to_string()
+        })
+    );
+    let kept = ours.merge(&theirs, MergePolicy::KeepOurs).unwrap();
+    assert_eq!(kept, ours);
+    let taken = ours.merge(&theirs, MergePolicy::TakeTheirs).unwrap();
+    assert_eq!(taken.node("a").unwrap().attr("color"), Some("blue"));
+    assert_eq!(
+        taken.edges(),
+        [Edge::new("a", "b").with_attrs(&[("style", "dashed")])]
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+#[ignore]
+fn test_json_round_trip() {
+    let graph = Graph::from_dot(
+        "strict digraph deps { rankdir=LR; node [shape=box]; a [color=red, label=A]; a -> b [weight=2]; subgraph cluster_0 { label=Zero; b } }",
+    )
+    .unwrap();
+
+    let json = graph.to_json();
+    assert_eq!(json, graph.clone().to_json());
+    assert!(json.contains("\"kind\": \"directed\""));
+    assert!(json.contains("\"color\": \"red\",\n        \"label\": \"A\""));
+    let parsed = Graph::from_json(&json).unwrap();
+    assert_eq!(parsed, graph);
+    assert_eq!(
+        parsed.subgraph("cluster_0").unwrap().attr("label"),
+        Some("Zero")
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+#[ignore]
+fn test_json_defaults_and_errors() {
+    let graph = Graph::from_json(
+        r#"{"nodes": [{"name": "a"}, {"name": "b"}], "edges": [{"from": "a", "to": "b"}]}"#,
+    )
+    .unwrap();
+
+    assert_eq!(graph.kind, GraphKind::Undirected);
+    assert_eq!(graph.nodes(), [Node::new("a"), Node::new("b")]);
+    assert_eq!(graph.edges(), [Edge::new("a", "b")]);
+    assert!(Graph::from_json(r#"{"kind": "mixed"}"#).is_err());
+    let err =
+        Graph::from_json(r#"{"nodes": [{"name": "a"}], "edges": [{"from": "a", "to": "b"}]}"#)
+            .unwrap_err();
+    assert!(err.to_string().contains("unknown node `b`"));
+    let err = Graph::from_json(r#"{"nodes": [{"name": "a"}, {"name": "a"}]}"#).unwrap_err();
+    assert!(err.to_string().contains("duplicate node `a`"));
+    // Nodes named by subgraphs only may still be joined by edges.
+    let graph = Graph::from_json(
+        r#"{"subgraphs": [{"nodes": ["a", "b"]}], "edges": [{"from": "a", "to": "b"}]}"#,
+    )
+    .unwrap();
+    assert_eq!(graph.edges(), [Edge::new("a", "b")]);
+}
@@ -0,0 +1,6 @@
+use dot_dsl::graph;
+
+fn main() {
+    // an edge needs a node at both ends
+    let _graph = graph! { a -> ; };
+}
@@ -0,0 +1,6 @@
+use dot_dsl::graph;
+
+fn main() {
+    // node names are identifiers or literals
+    let _graph = graph! { a -> (b); };
+}
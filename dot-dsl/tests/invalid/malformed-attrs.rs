@@ -0,0 +1,6 @@
+use dot_dsl::graph;
+
+fn main() {
+    // attributes are written `key = value`
+    let _graph = graph! { a [color "red"]; };
+}
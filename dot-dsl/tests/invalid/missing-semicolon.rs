@@ -0,0 +1,6 @@
+use dot_dsl::graph;
+
+fn main() {
+    // every statement ends with a semicolon
+    let _graph = graph! { a -> b };
+}
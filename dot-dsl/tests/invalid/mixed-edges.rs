@@ -0,0 +1,6 @@
+use dot_dsl::graph;
+
+fn main() {
+    // a graph is either directed or undirected
+    let _graph = graph! { a -> b; b -- c; };
+}
@@ -1,5 +1,9 @@
 use simple_linked_list::SimpleLinkedList;
 
+// Long enough to overflow the stack on a recursive drop, but kept short
+// under Miri, which runs these tests too.
+const LONG: usize = if cfg!(miri) { 1_000 } else { 20_000_000 };
+
 #[test]
 fn test_new_list_is_empty() {
     let list: SimpleLinkedList<u32> = SimpleLinkedList::new();
@@ -124,3 +128,460 @@ fn test_into_vector() {
     let s_as_vec: Vec<i32> = s.into();
     assert_eq!(v, s_as_vec);
 }
+
+#[test]
+#[ignore]
+fn test_peek_mut_changes_head_element() {
+    let mut list: SimpleLinkedList<u32> = SimpleLinkedList::new();
+    assert_eq!(list.peek_mut(), None);
+    list.push(1);
+    list.push(2);
+    if let Some(head) = list.peek_mut() {
+        *head *= 10;
+    }
+    assert_eq!(list.pop(), Some(20));
+    assert_eq!(list.pop(), Some(1));
+}
+
+#[test]
+#[ignore]
+fn test_iter_goes_from_head() {
+    let list: SimpleLinkedList<u32> = (1..4).collect();
+    assert_eq!(list.iter().collect::<Vec<_>>(), [&3, &2, &1]);
+    assert_eq!((&list).into_iter().count(), 3);
+    assert_eq!(list.len(), 3, "Iterating must not consume the list");
+}
+
+#[test]
+#[ignore]
+fn test_iter_mut_changes_elements() {
+    let mut list: SimpleLinkedList<u32> = (1..4).collect();
+    for element in list.iter_mut() {
+        *element += 1;
+    }
+    for element in &mut list {
+        *element *= 2;
+    }
+    let as_vec: Vec<u32> = list.into();
+    assert_eq!(as_vec, [4, 6, 8]);
+}
+
+#[test]
+#[ignore]
+fn test_into_iter_pops_elements() {
+    let list: SimpleLinkedList<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+    let mut collected = Vec::new();
+    for element in list {
+        collected.push(element);
+    }
+    assert_eq!(collected, ["c", "b", "a"]);
+}
+
+#[test]
+#[ignore]
+fn test_cursor_moves_through_list() {
+    let mut list: SimpleLinkedList<u32> = (1..3).collect();
+    let mut cursor = list.cursor_mut();
+    assert_eq!(cursor.current(), Some(&mut 2));
+    assert!(cursor.move_next());
+    assert_eq!(cursor.current(), Some(&mut 1));
+    assert!(cursor.move_next());
+    assert_eq!(cursor.current(), None);
+    assert!(!cursor.move_next());
+}
+
+#[test]
+#[ignore]
+fn test_cursor_insert_after() {
+    let mut list: SimpleLinkedList<u32> = SimpleLinkedList::new();
+    list.push(1);
+    let mut cursor = list.cursor_mut();
+    cursor.insert_after(2);
+    assert_eq!(cursor.current(), Some(&mut 1));
+    cursor.move_next();
+    cursor.move_next();
+    cursor.insert_after(3);
+    assert_eq!(cursor.current(), None);
+    cursor.insert_after(4);
+    assert_eq!(list.iter().collect::<Vec<_>>(), [&1, &2, &3, &4]);
+    assert_eq!(list.len(), 4);
+}
+
+#[test]
+#[ignore]
+fn test_cursor_remove_current() {
+    let mut list: SimpleLinkedList<u32> = (1..5).collect();
+    let mut cursor = list.cursor_mut();
+    cursor.move_next();
+    assert_eq!(cursor.remove_current(), Some(3));
+    assert_eq!(cursor.current(), Some(&mut 2));
+    cursor.move_next();
+    assert_eq!(cursor.remove_current(), Some(1));
+    assert_eq!(cursor.remove_current(), None);
+    assert_eq!(list.iter().collect::<Vec<_>>(), [&4, &2]);
+    assert_eq!(list.len(), 2);
+}
+
+#[test]
+#[ignore]
+fn test_cursor_split() {
+    let mut list: SimpleLinkedList<u32> = (1..6).collect();
+    let mut cursor = list.cursor_mut();
+    cursor.move_next();
+    cursor.move_next();
+    let mut tail = cursor.split();
+    assert_eq!(cursor.current(), None);
+    assert!(cursor.split().is_empty());
+    assert_eq!(list.iter().collect::<Vec<_>>(), [&5, &4]);
+    assert_eq!(tail.iter().collect::<Vec<_>>(), [&3, &2, &1]);
+    assert_eq!(tail.len(), 3);
+
+    let whole = tail.cursor_mut().split();
+    assert!(tail.is_empty());
+    assert_eq!(whole.len(), 3);
+}
+
+#[test]
+#[ignore]
+fn test_len_is_kept_across_operations() {
+    let mut list: SimpleLinkedList<u32> = (0..10).collect();
+    list.pop();
+    let mut cursor = list.cursor_mut();
+    cursor.move_next();
+    cursor.insert_after(100);
+    cursor.remove_current();
+    cursor.move_next();
+    cursor.move_next();
+    let tail = cursor.split();
+    assert_eq!(list.len(), list.iter().count());
+    assert_eq!(tail.len(), tail.iter().count());
+    assert_eq!(list.len() + tail.len(), 9);
+    let list = list.rev();
+    assert_eq!(list.len(), list.iter().count());
+}
+
+#[test]
+#[ignore]
+fn test_dropping_a_long_list_does_not_overflow_the_stack() {
+    let list: SimpleLinkedList<u8> = std::iter::repeat_n(0, LONG).collect();
+    assert_eq!(list.len(), LONG);
+    drop(list);
+}
+
+#[test]
+#[ignore]
+fn test_long_lists_survive_reversal_and_splitting() {
+    let mut list: SimpleLinkedList<usize> = (0..LONG / 2).collect();
+    let mut cursor = list.cursor_mut();
+    for _ in 0..LONG / 4 {
+        cursor.move_next();
+    }
+    let tail = cursor.split();
+    assert_eq!(list.len(), LONG / 4);
+    assert_eq!(tail.len(), LONG / 4);
+    let tail = tail.rev();
+    assert_eq!(tail.peek(), Some(&0));
+    let mut rest = tail.into_iter();
+    assert_eq!(rest.next(), Some(0));
+}
+
+#[test]
+#[ignore]
+fn test_persistent_list_versions_share_their_tails() {
+    use simple_linked_list::persistent::RcList;
+    let empty: RcList<u32> = RcList::new();
+    let one = empty.prepend(1);
+    let two = one.prepend(2);
+    let other_two = one.prepend(20);
+    assert!(empty.is_empty());
+    assert_eq!(one.iter().collect::<Vec<_>>(), [&1]);
+    assert_eq!(two.iter().collect::<Vec<_>>(), [&2, &1]);
+    assert_eq!(other_two.iter().collect::<Vec<_>>(), [&20, &1]);
+    assert!(two.tail().ptr_eq(&one));
+    assert!(other_two.tail().ptr_eq(&one));
+    assert_eq!(two.len(), 2);
+}
+
+#[test]
+#[ignore]
+fn test_persistent_list_head_and_tail() {
+    use simple_linked_list::persistent::RcList;
+    let list: RcList<&str> = ["a", "b", "c"].into_iter().collect();
+    assert_eq!(list.head(), Some(&"c"));
+    assert_eq!(list.tail().head(), Some(&"b"));
+    assert_eq!(list.tail().tail().tail().head(), None);
+    assert!(list.tail().tail().tail().tail().is_empty());
+    assert_eq!((&list).into_iter().count(), 3);
+}
+
+#[test]
+#[ignore]
+fn test_persistent_list_drops_shared_nodes_once() {
+    use simple_linked_list::persistent::RcList;
+    use std::rc::Rc;
+    let counter = Rc::new(());
+    let base: RcList<Rc<()>> = (0..3).map(|_| Rc::clone(&counter)).collect();
+    let branch = base.prepend(Rc::clone(&counter));
+    assert_eq!(Rc::strong_count(&counter), 5);
+    drop(base);
+    assert_eq!(Rc::strong_count(&counter), 5);
+    drop(branch);
+    assert_eq!(Rc::strong_count(&counter), 1);
+}
+
+#[test]
+#[ignore]
+fn test_arc_list_is_shared_between_threads() {
+    use simple_linked_list::persistent::ArcList;
+    let base: ArcList<u32> = (1..=100).collect();
+    let handles = (0..4)
+        .map(|n| {
+            let base = base.clone();
+            std::thread::spawn(move || base.prepend(n).iter().sum::<u32>())
+        })
+        .collect::<Vec<_>>();
+    let sums = handles
+        .into_iter()
+        .map(|handle| handle.join().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(sums, [5050, 5051, 5052, 5053]);
+    assert_eq!(base.len(), 100);
+}
+
+#[test]
+#[ignore]
+fn test_dropping_long_persistent_lists_does_not_overflow_the_stack() {
+    use simple_linked_list::persistent::{ArcList, RcList};
+    let rc_list: RcList<u8> = std::iter::repeat_n(0, LONG / 2).collect();
+    let shared = rc_list.tail();
+    drop(rc_list);
+    assert_eq!(shared.len(), LONG / 2 - 1);
+    drop(shared);
+    let arc_list: ArcList<u8> = std::iter::repeat_n(0, LONG / 2).collect();
+    drop(arc_list);
+}
+
+fn doubly(values: &[u32]) -> simple_linked_list::doubly::DoublyLinkedList<u32> {
+    values.iter().copied().collect()
+}
+
+fn contents(list: &simple_linked_list::doubly::DoublyLinkedList<u32>) -> Vec<u32> {
+    list.iter().copied().collect()
+}
+
+#[test]
+#[ignore]
+fn test_doubly_push_and_pop_at_both_ends() {
+    let mut list = doubly(&[]);
+    assert_eq!(list.pop_front(), None);
+    assert_eq!(list.pop_back(), None);
+    list.push_back(2);
+    list.push_front(1);
+    list.push_back(3);
+    assert_eq!(list.front(), Some(&1));
+    assert_eq!(list.back(), Some(&3));
+    *list.front_mut().unwrap() += 10;
+    *list.back_mut().unwrap() += 10;
+    assert_eq!(list.len(), 3);
+    assert_eq!(list.pop_back(), Some(13));
+    assert_eq!(list.pop_front(), Some(11));
+    assert_eq!(list.pop_front(), Some(2));
+    assert!(list.is_empty());
+    assert_eq!(list.back(), None);
+}
+
+#[test]
+#[ignore]
+fn test_doubly_iterates_both_ways() {
+    let mut list = doubly(&[1, 2, 3, 4]);
+    assert_eq!(list.iter().rev().copied().collect::<Vec<_>>(), [4, 3, 2, 1]);
+    let mut iter = list.iter();
+    assert_eq!(iter.len(), 4);
+    assert_eq!(iter.next(), Some(&1));
+    assert_eq!(iter.next_back(), Some(&4));
+    assert_eq!(iter.next(), Some(&2));
+    assert_eq!(iter.next_back(), Some(&3));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+
+    for element in &mut list {
+        *element *= 10;
+    }
+    let mut iter = list.iter_mut();
+    *iter.next_back().unwrap() += 1;
+    assert_eq!(contents(&list), [10, 20, 30, 41]);
+
+    let mut owned = list.into_iter();
+    assert_eq!(owned.next_back(), Some(41));
+    assert_eq!(owned.collect::<Vec<_>>(), [10, 20, 30]);
+}
+
+#[test]
+#[ignore]
+fn test_doubly_append() {
+    let mut list = doubly(&[1, 2]);
+    let mut other = doubly(&[3, 4]);
+    list.append(&mut other);
+    assert_eq!(contents(&list), [1, 2, 3, 4]);
+    assert!(other.is_empty());
+    assert_eq!(list.len(), 4);
+
+    other.append(&mut list);
+    assert_eq!(contents(&other), [1, 2, 3, 4]);
+    assert!(list.is_empty());
+    other.append(&mut list);
+    assert_eq!(other.len(), 4);
+    assert_eq!(other.iter().rev().count(), 4);
+}
+
+#[test]
+#[ignore]
+fn test_doubly_split_off() {
+    for at in 0..=5 {
+        let mut list = doubly(&[0, 1, 2, 3, 4]);
+        let rest = list.split_off(at);
+        assert_eq!(contents(&list), (0..at as u32).collect::<Vec<_>>());
+        assert_eq!(contents(&rest), (at as u32..5).collect::<Vec<_>>());
+        assert_eq!(list.len(), at);
+        assert_eq!(rest.len(), 5 - at);
+        assert_eq!(list.iter().rev().count(), at);
+        assert_eq!(rest.iter().rev().count(), 5 - at);
+    }
+}
+
+#[test]
+#[ignore]
+#[should_panic]
+fn test_doubly_split_off_past_the_end_panics() {
+    doubly(&[1, 2]).split_off(3);
+}
+
+#[test]
+#[ignore]
+fn test_doubly_cursor_moves_both_ways_through_the_ghost() {
+    let mut list = doubly(&[1, 2, 3]);
+    let mut cursor = list.cursor_front_mut();
+    assert_eq!((cursor.index(), cursor.current()), (Some(0), Some(&mut 1)));
+    cursor.move_prev();
+    assert_eq!((cursor.index(), cursor.current()), (None, None));
+    cursor.move_prev();
+    assert_eq!((cursor.index(), cursor.current()), (Some(2), Some(&mut 3)));
+    cursor.move_next();
+    cursor.move_next();
+    assert_eq!((cursor.index(), cursor.current()), (Some(0), Some(&mut 1)));
+
+    let mut empty = doubly(&[]);
+    let mut cursor = empty.cursor_back_mut();
+    assert_eq!(cursor.current(), None);
+    cursor.move_next();
+    cursor.move_prev();
+    assert_eq!(cursor.index(), None);
+}
+
+#[test]
+#[ignore]
+fn test_doubly_cursor_insert_and_remove() {
+    let mut list = doubly(&[1, 3]);
+    let mut cursor = list.cursor_front_mut();
+    cursor.insert_after(2);
+    assert_eq!(cursor.current(), Some(&mut 1));
+    cursor.move_prev();
+    cursor.insert_after(0);
+    cursor.move_prev();
+    cursor.insert_after(4);
+    assert_eq!(cursor.current(), Some(&mut 3));
+    cursor.move_next();
+    assert_eq!(cursor.remove_current(), Some(4));
+    assert_eq!(cursor.current(), None);
+    assert_eq!(contents(&list), [0, 1, 2, 3]);
+
+    let mut cursor = list.cursor_front_mut();
+    cursor.move_next();
+    assert_eq!(cursor.remove_current(), Some(1));
+    assert_eq!((cursor.index(), cursor.current()), (Some(1), Some(&mut 2)));
+    assert_eq!(cursor.remove_current(), Some(2));
+    assert_eq!(cursor.remove_current(), Some(3));
+    assert_eq!(cursor.remove_current(), None);
+    assert_eq!(contents(&list), [0]);
+    assert_eq!(list.back(), Some(&0));
+    assert_eq!(list.len(), 1);
+}
+
+#[test]
+#[ignore]
+fn test_doubly_cursor_splice_after() {
+    let mut list = doubly(&[1, 4]);
+    let mut cursor = list.cursor_front_mut();
+    cursor.splice_after(doubly(&[2, 3]));
+    assert_eq!(cursor.current(), Some(&mut 1));
+    cursor.splice_after(doubly(&[]));
+    cursor.move_prev();
+    cursor.splice_after(doubly(&[u32::MAX, 0]));
+    assert_eq!(cursor.index(), None);
+    cursor.move_prev();
+    cursor.splice_after(doubly(&[5, 6]));
+    assert_eq!(cursor.current(), Some(&mut 4));
+    assert_eq!(contents(&list), [u32::MAX, 0, 1, 2, 3, 4, 5, 6]);
+    assert_eq!(
+        list.iter().rev().copied().collect::<Vec<_>>(),
+        [6, 5, 4, 3, 2, 1, 0, u32::MAX]
+    );
+    assert_eq!(list.len(), 8);
+}
+
+#[test]
+#[ignore]
+fn test_doubly_cursor_split_after() {
+    let mut list = doubly(&[1, 2, 3, 4]);
+    let mut cursor = list.cursor_back_mut();
+    cursor.move_prev();
+    cursor.move_prev();
+    let rest = cursor.split_after();
+    assert_eq!(cursor.current(), Some(&mut 2));
+    cursor.move_next();
+    let whole = cursor.split_after();
+    assert_eq!(contents(&rest), [3, 4]);
+    assert_eq!(contents(&whole), [1, 2]);
+    assert!(list.is_empty());
+    assert_eq!(rest.back(), Some(&4));
+    assert_eq!(whole.back(), Some(&2));
+}
+
+#[test]
+#[ignore]
+fn test_doubly_drops_every_element_once() {
+    use std::rc::Rc;
+    let counter = Rc::new(());
+    let mut list: simple_linked_list::doubly::DoublyLinkedList<_> =
+        (0..6).map(|_| Rc::clone(&counter)).collect();
+    let mut rest = list.split_off(2);
+    let mut cursor = rest.cursor_front_mut();
+    cursor.remove_current();
+    cursor.splice_after(list);
+    assert_eq!(Rc::strong_count(&counter), 6);
+    let mut iter = rest.into_iter();
+    iter.next();
+    iter.next_back();
+    assert_eq!(Rc::strong_count(&counter), 4);
+    drop(iter);
+    assert_eq!(Rc::strong_count(&counter), 1);
+}
+
+#[test]
+#[ignore]
+fn test_doubly_sends_between_threads() {
+    let list = doubly(&[1, 2, 3]);
+    let sum = std::thread::spawn(move || list.iter().sum::<u32>())
+        .join()
+        .unwrap();
+    assert_eq!(sum, 6);
+}
+
+#[test]
+#[ignore]
+fn test_dropping_a_long_doubly_linked_list_does_not_overflow_the_stack() {
+    let list: simple_linked_list::doubly::DoublyLinkedList<u8> =
+        std::iter::repeat_n(0, LONG).collect();
+    assert_eq!(list.len(), LONG);
+    drop(list);
+}
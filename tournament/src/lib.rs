@@ -1,102 +1,1266 @@
+use std::cmp::Ordering;
 use std::collections::HashMap;
 
-#[derive(Clone)]
-struct Result {
-    team_name: String,
-    played: u64,
-    points: u64,
-    won: u64,
-    drawn: u64,
-    lost: u64,
+use unicode_width::UnicodeWidthStr;
+
+// Why a line of the input is not a match result.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Reason {
+    // A result has exactly three fields separated by `;`.
+    WrongFieldCount(usize),
+    EmptyTeamName,
+    SameTeam,
+    UnknownVerdict(String),
+    // A score such as `3-x` that is not two goal counts.
+    InvalidScore(String),
+    // A knockout result for a match the bracket is not waiting on.
+    NotAFixture,
+    // A knockout match needs a winner.
+    DrawnKnockout,
+}
+
+// How teams level on points are ordered, applied in turn until they differ.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TieBreaker {
+    GoalDifference,
+    GoalsScored,
+    // Points taken in the matches between the teams still level.
+    HeadToHead,
+    Name,
+}
+
+// Points awarded per match and how ties on points are broken.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Rules {
+    pub win: u64,
+    pub draw: u64,
+    pub loss: u64,
+    pub tie_breakers: Vec<TieBreaker>,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            win: 3,
+            draw: 1,
+            loss: 0,
+            tie_breakers: vec![TieBreaker::Name],
+        }
+    }
 }
 
-impl Result {
+// A line that could not be read, `line` counting from 1.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TallyError {
+    pub line: usize,
+    pub content: String,
+    pub reason: Reason,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Outcome {
+    Win,
+    Draw,
+    Loss,
+}
+
+impl Outcome {
+    fn reversed(self) -> Self {
+        match self {
+            Outcome::Win => Outcome::Loss,
+            Outcome::Draw => Outcome::Draw,
+            Outcome::Loss => Outcome::Win,
+        }
+    }
+}
+
+// A match as read from a line of the input, the outcome and goals being the first team's.
+struct Match<'a> {
+    first_team: &'a str,
+    second_team: &'a str,
+    outcome: Outcome,
+    goals: (u64, u64),
+}
+
+impl Match<'_> {
+    // Each team with its outcome and goals for and against.
+    fn sides(&self) -> [(&str, Outcome, (u64, u64)); 2] {
+        let (scored, conceded) = self.goals;
+        [
+            (self.first_team, self.outcome, (scored, conceded)),
+            (
+                self.second_team,
+                self.outcome.reversed(),
+                (conceded, scored),
+            ),
+        ]
+    }
+}
+
+// A team's row in the standings.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TeamResult {
+    pub team_name: String,
+    pub played: u64,
+    pub points: u64,
+    pub won: u64,
+    pub drawn: u64,
+    pub lost: u64,
+    pub goals_for: u64,
+    pub goals_against: u64,
+}
+
+impl TeamResult {
     fn new_empty(team_name: String) -> Self {
-        Result {
+        TeamResult {
             team_name,
             played: 0,
             points: 0,
             won: 0,
             drawn: 0,
             lost: 0,
+            goals_for: 0,
+            goals_against: 0,
         }
     }
+
+    pub fn goal_difference(&self) -> i64 {
+        self.goals_for as i64 - self.goals_against as i64
+    }
+
+    fn record(&mut self, rules: &Rules, outcome: Outcome, (scored, conceded): (u64, u64)) {
+        self.played += 1;
+        self.goals_for += scored;
+        self.goals_against += conceded;
+        match outcome {
+            Outcome::Win => self.won += 1,
+            Outcome::Draw => self.drawn += 1,
+            Outcome::Loss => self.lost += 1,
+        }
+        self.points += rules.points(outcome);
+    }
+
+    // Takes back a match added by `record`.
+    fn unrecord(&mut self, rules: &Rules, outcome: Outcome, (scored, conceded): (u64, u64)) {
+        self.played -= 1;
+        self.goals_for -= scored;
+        self.goals_against -= conceded;
+        match outcome {
+            Outcome::Win => self.won -= 1,
+            Outcome::Draw => self.drawn -= 1,
+            Outcome::Loss => self.lost -= 1,
+        }
+        self.points -= rules.points(outcome);
+    }
+}
+
+// The teams in ranking order, rendered by the `to_*` methods.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Standings {
+    rows: Vec<TeamResult>,
 }
 
-fn format_output(results: &Vec<Result>) -> String {
-    let mut output = String::new();
-    output.push_str(
-        format!(
-            "{:<31}| {:^3}| {:^3}| {:^3}| {:^3}|{:>3}",
-            "Team", "MP", "W", "D", "L", "P"
-        )
-        .as_str(),
-    );
-    for result in results {
-        output.push_str(
+const HEADERS: [&str; 6] = ["Team", "MP", "W", "D", "L", "P"];
+
+impl Standings {
+    pub fn rows(&self) -> &[TeamResult] {
+        &self.rows
+    }
+
+    pub fn team(&self, team_name: &str) -> Option<&TeamResult> {
+        self.rows.iter().find(|row| row.team_name == team_name)
+    }
+
+    // The columns shown by the tables, the team name first.
+    fn cells(row: &TeamResult) -> [String; 6] {
+        [
+            row.team_name.clone(),
+            row.played.to_string(),
+            row.won.to_string(),
+            row.drawn.to_string(),
+            row.lost.to_string(),
+            row.points.to_string(),
+        ]
+    }
+
+    // The display width of the team column, at least the classic 30.
+    fn team_width(&self) -> usize {
+        self.rows
+            .iter()
+            .map(|row| row.team_name.width())
+            .fold(30, usize::max)
+    }
+
+    // The fixed-width table returned by `tally`.
+    pub fn to_text(&self) -> String {
+        let width = self.team_width();
+        let line = |cells: &[&str]| {
+            format!(
+                "{}| {:^3}| {:^3}| {:^3}| {:^3}|{:>3}",
+                pad(cells[0], width + 1),
+                cells[1],
+                cells[2],
+                cells[3],
+                cells[4],
+                cells[5]
+            )
+        };
+        let mut lines = vec![line(&HEADERS)];
+        for row in &self.rows {
+            let cells = Self::cells(row);
+            lines.push(line(&cells.each_ref().map(String::as_str)));
+        }
+        lines.join("\n")
+    }
+
+    // One line per team, with goals, under a header line.
+    pub fn to_csv(&self) -> String {
+        let mut lines = vec!["Team,MP,W,D,L,GF,GA,GD,P".to_string()];
+        for row in &self.rows {
+            lines.push(format!(
+                "{},{},{},{},{},{},{},{},{}",
+                csv_field(&row.team_name),
+                row.played,
+                row.won,
+                row.drawn,
+                row.lost,
+                row.goals_for,
+                row.goals_against,
+                row.goal_difference(),
+                row.points
+            ));
+        }
+        lines.join("\n")
+    }
+
+    // An array with one object per team, with goals.
+    pub fn to_json(&self) -> String {
+        if self.rows.is_empty() {
+            return "[]".to_string();
+        }
+        let objects = self
+            .rows
+            .iter()
+            .map(|row| {
+                format!(
+                    "  {{\"team\": {}, \"played\": {}, \"won\": {}, \"drawn\": {}, \"lost\": {}, \
+                     \"goals_for\": {}, \"goals_against\": {}, \"goal_difference\": {}, \"points\": {}}}",
+                    json_string(&row.team_name),
+                    row.played,
+                    row.won,
+                    row.drawn,
+                    row.lost,
+                    row.goals_for,
+                    row.goals_against,
+                    row.goal_difference(),
+                    row.points
+                )
+            })
+            .collect::<Vec<_>>();
+        format!("[\n{}\n]", objects.join(",\n"))
+    }
+
+    // A GitHub-flavoured table, padded so that the source lines up too.
+    pub fn to_markdown(&self) -> String {
+        let names = self
+            .rows
+            .iter()
+            .map(|row| row.team_name.replace('|', "\\|"))
+            .collect::<Vec<_>>();
+        let width = names.iter().map(|name| name.width()).fold(4, usize::max);
+        let line = |cells: &[&str]| {
             format!(
-                "\n{:<31}| {:^3}| {:^3}| {:^3}| {:^3}|{:>3}",
-                result.team_name,
-                result.played,
-                result.won,
-                result.drawn,
-                result.lost,
-                result.points
+                "| {} | {:>2} | {:>2} | {:>2} | {:>2} | {:>2} |",
+                pad(cells[0], width),
+                cells[1],
+                cells[2],
+                cells[3],
+                cells[4],
+                cells[5]
             )
-            .as_str(),
-        );
+        };
+        let mut lines = vec![
+            line(&HEADERS),
+            format!("|:{}|---:|---:|---:|---:|---:|", "-".repeat(width + 1)),
+        ];
+        for (row, name) in self.rows.iter().zip(&names) {
+            let mut cells = Self::cells(row);
+            cells[0] = name.clone();
+            lines.push(line(&cells.each_ref().map(String::as_str)));
+        }
+        lines.join("\n")
+    }
+
+    pub fn to_html(&self) -> String {
+        let line = |tag: &str, cells: &[&str]| {
+            let cells = cells
+                .iter()
+                .map(|cell| format!("<{tag}>{}</{tag}>", html_escape(cell)))
+                .collect::<String>();
+            format!("    <tr>{cells}</tr>")
+        };
+        let mut lines = vec![
+            "<table>".to_string(),
+            "  <thead>".to_string(),
+            line("th", &HEADERS),
+            "  </thead>".to_string(),
+            "  <tbody>".to_string(),
+        ];
+        for row in &self.rows {
+            let cells = Self::cells(row);
+            lines.push(line("td", &cells.each_ref().map(String::as_str)));
+        }
+        lines.push("  </tbody>".to_string());
+        lines.push("</table>".to_string());
+        lines.join("\n")
+    }
+}
+
+// Pads `text` with spaces to `width` columns as displayed in a terminal.
+fn pad(text: &str, width: usize) -> String {
+    format!("{text}{}", " ".repeat(width.saturating_sub(text.width())))
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn json_string(text: &str) -> String {
+    let mut escaped = String::from('"');
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn parse_match(line: &str) -> Result<Match<'_>, Reason> {
+    let fields: Vec<&str> = line.split(';').map(|field| field.trim()).collect();
+    let [first_team, second_team, verdict] = fields.as_slice() else {
+        return Err(Reason::WrongFieldCount(fields.len()));
+    };
+    if first_team.is_empty() || second_team.is_empty() {
+        return Err(Reason::EmptyTeamName);
     }
-    output
+    if first_team == second_team {
+        return Err(Reason::SameTeam);
+    }
+    let (outcome, goals) = match *verdict {
+        "win" => (Outcome::Win, (0, 0)),
+        "draw" => (Outcome::Draw, (0, 0)),
+        "loss" => (Outcome::Loss, (0, 0)),
+        _ => match verdict.split_once('-') {
+            Some((scored, conceded)) => {
+                let goals = scored
+                    .trim()
+                    .parse::<u64>()
+                    .and_then(|scored| Ok((scored, conceded.trim().parse::<u64>()?)))
+                    .map_err(|_| Reason::InvalidScore(verdict.to_string()))?;
+                let outcome = match goals.0.cmp(&goals.1) {
+                    Ordering::Greater => Outcome::Win,
+                    Ordering::Equal => Outcome::Draw,
+                    Ordering::Less => Outcome::Loss,
+                };
+                (outcome, goals)
+            }
+            None => return Err(Reason::UnknownVerdict(verdict.to_string())),
+        },
+    };
+    Ok(Match {
+        first_team,
+        second_team,
+        outcome,
+        goals,
+    })
+}
+
+// The lines of the input that are not blank, numbered from 1.
+fn lines(match_results: &str) -> impl Iterator<Item = (usize, &str)> {
+    match_results
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| (idx + 1, line))
+}
+
+// Reads every line of the input, blank lines aside, as a match result.
+fn parse(match_results: &str) -> impl Iterator<Item = Result<Match<'_>, TallyError>> {
+    lines(match_results).map(|(line, content)| {
+        parse_match(content).map_err(|reason| TallyError {
+            line,
+            content: content.to_string(),
+            reason,
+        })
+    })
 }
 
+impl Rules {
+    pub fn with_points(mut self, win: u64, draw: u64, loss: u64) -> Self {
+        self.win = win;
+        self.draw = draw;
+        self.loss = loss;
+        self
+    }
+
+    pub fn with_tie_breakers(mut self, tie_breakers: &[TieBreaker]) -> Self {
+        self.tie_breakers = tie_breakers.to_vec();
+        self
+    }
+
+    fn points(&self, outcome: Outcome) -> u64 {
+        match outcome {
+            Outcome::Win => self.win,
+            Outcome::Draw => self.draw,
+            Outcome::Loss => self.loss,
+        }
+    }
+
+    // Panics on the first line that is not a match result, see `try_tally`.
+    pub fn tally(&self, match_results: &str) -> String {
+        match self.try_tally(match_results) {
+            Ok(table) => table,
+            Err(error) => panic!(
+                "Line {} is not a match result ({:?}): {}",
+                error.line, error.reason, error.content
+            ),
+        }
+    }
+
+    pub fn try_tally(&self, match_results: &str) -> Result<String, TallyError> {
+        Ok(self.try_standings(match_results)?.to_text())
+    }
+
+    // Tallies the lines that are match results, returning the others alongside the table.
+    pub fn tally_lenient(&self, match_results: &str) -> (String, Vec<TallyError>) {
+        let (standings, errors) = self.standings_lenient(match_results);
+        (standings.to_text(), errors)
+    }
+
+    pub fn try_standings(&self, match_results: &str) -> Result<Standings, TallyError> {
+        let matches = parse(match_results).collect::<Result<Vec<_>, _>>()?;
+        Ok(self.rank(&matches))
+    }
+
+    // Ranks the lines that are match results, returning the others alongside the standings.
+    pub fn standings_lenient(&self, match_results: &str) -> (Standings, Vec<TallyError>) {
+        let mut matches = Vec::new();
+        let mut errors = Vec::new();
+        for parsed in parse(match_results) {
+            match parsed {
+                Ok(game) => matches.push(game),
+                Err(error) => errors.push(error),
+            }
+        }
+        (self.rank(&matches), errors)
+    }
+
+    fn rank(&self, matches: &[Match]) -> Standings {
+        let mut results: HashMap<&str, TeamResult> = HashMap::new();
+        for game in matches {
+            for (team, outcome, goals) in game.sides() {
+                results
+                    .entry(team)
+                    .or_insert_with(|| TeamResult::new_empty(team.to_string()))
+                    .record(self, outcome, goals);
+            }
+        }
+        self.order(results.into_values().collect(), matches)
+    }
+
+    fn order(&self, mut results: Vec<TeamResult>, matches: &[Match]) -> Standings {
+        results.sort_by(|a, b| a.points.cmp(&b.points).reverse());
+        for group in results.chunk_by_mut(|a, b| a.points == b.points) {
+            self.break_ties(group, matches, &self.tie_breakers);
+        }
+        Standings { rows: results }
+    }
+
+    // Orders teams level so far by the first tie-breaker, then each group
+    // still level by the rest, falling back on the name.
+    fn break_ties(&self, group: &mut [TeamResult], matches: &[Match], tie_breakers: &[TieBreaker]) {
+        let Some((&tie_breaker, rest)) = tie_breakers.split_first() else {
+            group.sort_by(|a, b| a.team_name.cmp(&b.team_name));
+            return;
+        };
+        if group.len() < 2 {
+            return;
+        }
+        let keys: HashMap<String, i64> = match tie_breaker {
+            TieBreaker::GoalDifference => group
+                .iter()
+                .map(|team| (team.team_name.clone(), team.goal_difference()))
+                .collect(),
+            TieBreaker::GoalsScored => group
+                .iter()
+                .map(|team| (team.team_name.clone(), team.goals_for as i64))
+                .collect(),
+            TieBreaker::HeadToHead => self.head_to_head(group, matches),
+            TieBreaker::Name => {
+                group.sort_by(|a, b| a.team_name.cmp(&b.team_name));
+                return;
+            }
+        };
+        group.sort_by(|a, b| keys[&a.team_name].cmp(&keys[&b.team_name]).reverse());
+        for level in group.chunk_by_mut(|a, b| keys[&a.team_name] == keys[&b.team_name]) {
+            self.break_ties(level, matches, rest);
+        }
+    }
+
+    // Points each team of the group took in the matches between them.
+    fn head_to_head(&self, group: &[TeamResult], matches: &[Match]) -> HashMap<String, i64> {
+        let mut points: HashMap<String, i64> = group
+            .iter()
+            .map(|team| (team.team_name.clone(), 0))
+            .collect();
+        for game in matches {
+            if points.contains_key(game.first_team) && points.contains_key(game.second_team) {
+                for (team, outcome, _) in game.sides() {
+                    *points.get_mut(team).unwrap() += self.points(outcome) as i64;
+                }
+            }
+        }
+        points
+    }
+}
+
+// Panics on the first line that is not a match result, see `try_tally`.
 pub fn tally(match_results: &str) -> String {
-    let mut results: HashMap<&str, Result> = HashMap::new();
-    for result in match_results.lines() {
-        let result_parts: Vec<_> = result.split(';').collect();
-        match result_parts.as_slice() {
-            [first_team, second_team, verdict] => {
-                let mut first_team_stats = results
-                    .get(first_team)
-                    .cloned()
-                    .unwrap_or_else(|| Result::new_empty(first_team.to_string()));
-                let mut second_team_stats = results
-                    .get(second_team)
-                    .cloned()
-                    .unwrap_or_else(|| Result::new_empty(second_team.to_string()));
-                match *verdict {
-                    "win" => {
-                        first_team_stats.won += 1;
-                        second_team_stats.lost += 1;
-                        first_team_stats.points += 3;
-                    }
-                    "loss" => {
-                        second_team_stats.won += 1;
-                        first_team_stats.lost += 1;
-                        second_team_stats.points += 3;
-                    }
-                    "draw" => {
-                        first_team_stats.drawn += 1;
-                        second_team_stats.drawn += 1;
-                        first_team_stats.points += 1;
-                        second_team_stats.points += 1;
+    Rules::default().tally(match_results)
+}
+
+pub fn try_tally(match_results: &str) -> Result<String, TallyError> {
+    Rules::default().try_tally(match_results)
+}
+
+// Tallies the lines that are match results, returning the others alongside the table.
+pub fn tally_lenient(match_results: &str) -> (String, Vec<TallyError>) {
+    Rules::default().tally_lenient(match_results)
+}
+
+pub fn try_standings(match_results: &str) -> Result<Standings, TallyError> {
+    Rules::default().try_standings(match_results)
+}
+
+// How ratings move after each match: the winner takes points from the
+// loser in proportion to how unexpected the result was.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Elo {
+    pub k_factor: f64,
+    // Rating points added to the home team when predicting the result.
+    pub home_advantage: f64,
+    pub initial_rating: f64,
+}
+
+impl Default for Elo {
+    fn default() -> Self {
+        Elo {
+            k_factor: 32.0,
+            home_advantage: 0.0,
+            initial_rating: 1500.0,
+        }
+    }
+}
+
+impl Elo {
+    pub fn with_k_factor(mut self, k_factor: f64) -> Self {
+        self.k_factor = k_factor;
+        self
+    }
+
+    pub fn with_home_advantage(mut self, home_advantage: f64) -> Self {
+        self.home_advantage = home_advantage;
+        self
+    }
+
+    pub fn with_initial_rating(mut self, initial_rating: f64) -> Self {
+        self.initial_rating = initial_rating;
+        self
+    }
+
+    // The home team's expected score, 1 for a certain win.
+    pub fn expected_score(&self, home_rating: f64, away_rating: f64) -> f64 {
+        1.0 / (1.0 + 10f64.powf((away_rating - home_rating - self.home_advantage) / 400.0))
+    }
+
+    fn update(&self, ratings: &mut HashMap<String, f64>, game: &Match) {
+        let home = ratings[game.first_team];
+        let away = ratings[game.second_team];
+        let score = match game.outcome {
+            Outcome::Win => 1.0,
+            Outcome::Draw => 0.5,
+            Outcome::Loss => 0.0,
+        };
+        let change = self.k_factor * (score - self.expected_score(home, away));
+        *ratings.get_mut(game.first_team).unwrap() += change;
+        *ratings.get_mut(game.second_team).unwrap() -= change;
+    }
+}
+
+// Identifies a result recorded in a `Tournament`, to remove it later.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct MatchId(usize);
+
+// A match result owned by a `Tournament`, the first team being at home.
+#[derive(Debug, Clone)]
+struct Recorded {
+    home: String,
+    away: String,
+    outcome: Outcome,
+    goals: (u64, u64),
+}
+
+impl Recorded {
+    fn as_match(&self) -> Match<'_> {
+        Match {
+            first_team: &self.home,
+            second_team: &self.away,
+            outcome: self.outcome,
+            goals: self.goals,
+        }
+    }
+}
+
+// A league updated one result at a time, rather than tallied from scratch.
+#[derive(Debug, Clone, Default)]
+pub struct Tournament {
+    rules: Rules,
+    elo: Elo,
+    // Indexed by `MatchId`, `None` once removed.
+    matches: Vec<Option<Recorded>>,
+    teams: HashMap<String, TeamResult>,
+    ratings: HashMap<String, f64>,
+}
+
+impl Tournament {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_rules(mut self, rules: Rules) -> Self {
+        self.rules = rules;
+        self.recompute();
+        self
+    }
+
+    pub fn with_elo(mut self, elo: Elo) -> Self {
+        self.elo = elo;
+        self.recompute();
+        self
+    }
+
+    // Records one result in the `tally` format, such as `A;B;2-1`. Being
+    // a single result rather than a line of input, a malformed one only
+    // comes with the reason it was rejected.
+    pub fn record(&mut self, result: &str) -> Result<MatchId, Reason> {
+        let game = parse_match(result.trim())?;
+        for team in [game.first_team, game.second_team] {
+            self.ratings
+                .entry(team.to_string())
+                .or_insert(self.elo.initial_rating);
+        }
+        self.elo.update(&mut self.ratings, &game);
+        self.add_to_table(&game);
+        self.matches.push(Some(Recorded {
+            home: game.first_team.to_string(),
+            away: game.second_team.to_string(),
+            outcome: game.outcome,
+            goals: game.goals,
+        }));
+        Ok(MatchId(self.matches.len() - 1))
+    }
+
+    // Takes back a mistaken result, returning false if it was already removed.
+    // The table is updated in place, but ratings depend on the order of the
+    // matches and are replayed from the start.
+    pub fn remove(&mut self, id: MatchId) -> bool {
+        let Some(recorded) = self.matches.get_mut(id.0).and_then(Option::take) else {
+            return false;
+        };
+        for (team, outcome, goals) in recorded.as_match().sides() {
+            let row = self.teams.get_mut(team).unwrap();
+            row.unrecord(&self.rules, outcome, goals);
+            if row.played == 0 {
+                self.teams.remove(team);
+            }
+        }
+        self.replay_ratings();
+        true
+    }
+
+    pub fn standings(&self) -> Standings {
+        self.order(self.teams.values().cloned().collect())
+    }
+
+    // Ranks rows taken from the table, looking at the matches recorded if
+    // the rules break ties head to head.
+    fn order(&self, rows: Vec<TeamResult>) -> Standings {
+        if self.rules.tie_breakers.contains(&TieBreaker::HeadToHead) {
+            let matches = self.recorded().map(Recorded::as_match).collect::<Vec<_>>();
+            self.rules.order(rows, &matches)
+        } else {
+            self.rules.order(rows, &[])
+        }
+    }
+
+    pub fn rating(&self, team: &str) -> Option<f64> {
+        self.ratings.get(team).copied()
+    }
+
+    // Every team's rating, highest first.
+    pub fn ratings(&self) -> Vec<(&str, f64)> {
+        let mut ratings = self
+            .ratings
+            .iter()
+            .map(|(team, &rating)| (team.as_str(), rating))
+            .collect::<Vec<_>>();
+        ratings.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        ratings
+    }
+
+    fn recorded(&self) -> impl Iterator<Item = &Recorded> {
+        self.matches.iter().flatten()
+    }
+
+    fn add_to_table(&mut self, game: &Match) {
+        for (team, outcome, goals) in game.sides() {
+            self.teams
+                .entry(team.to_string())
+                .or_insert_with(|| TeamResult::new_empty(team.to_string()))
+                .record(&self.rules, outcome, goals);
+        }
+    }
+
+    fn replay_ratings(&mut self) {
+        let mut ratings = HashMap::new();
+        for recorded in self.recorded() {
+            for team in [&recorded.home, &recorded.away] {
+                ratings
+                    .entry(team.clone())
+                    .or_insert(self.elo.initial_rating);
+            }
+            self.elo.update(&mut ratings, &recorded.as_match());
+        }
+        self.ratings = ratings;
+    }
+
+    // Rebuilds the table and ratings after the rules or Elo settings change.
+    fn recompute(&mut self) {
+        let matches = self.recorded().cloned().collect::<Vec<_>>();
+        self.teams.clear();
+        for recorded in &matches {
+            self.add_to_table(&recorded.as_match());
+        }
+        self.replay_ratings();
+    }
+}
+
+pub mod fixtures {
+    use crate::{lines, parse_match, Outcome, Reason, TallyError};
+
+    #[derive(Debug, PartialEq, Eq, Clone)]
+    pub enum FixtureError {
+        DuplicateTeam(String),
+        // Fewer than two teams make no match to schedule.
+        TooFewTeams,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone)]
+    pub struct Fixture {
+        pub home: String,
+        pub away: String,
+    }
+
+    impl Fixture {
+        // The fixture as a line of `tally` input, `verdict` being the home team's.
+        pub fn result_line(&self, verdict: &str) -> String {
+            format!("{};{};{}", self.home, self.away, verdict)
+        }
+    }
+
+    // The fixtures played at the same time, numbered from 1.
+    #[derive(Debug, PartialEq, Eq, Clone)]
+    pub struct Round {
+        pub number: usize,
+        pub fixtures: Vec<Fixture>,
+        pub bye: Option<String>,
+    }
+
+    fn check_teams(teams: &[&str]) -> Result<(), FixtureError> {
+        if teams.len() < 2 {
+            return Err(FixtureError::TooFewTeams);
+        }
+        for (idx, team) in teams.iter().enumerate() {
+            if teams[..idx].contains(team) {
+                return Err(FixtureError::DuplicateTeam(team.to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    // Every team plays every other once, by the circle method: the first
+    // team stays put while the others rotate around it. Venues follow the
+    // Berger tables: the fixed team alternates each round and the other
+    // pairs alternate from one place to the next, so no team plays more
+    // than two matches in a row at home or away. With an odd count the
+    // fixed place is left empty, its opponent sitting out the round, and
+    // every team then alternates strictly.
+    pub fn round_robin(teams: &[&str]) -> Result<Vec<Round>, FixtureError> {
+        check_teams(teams)?;
+        let mut circle = teams.iter().map(|&team| Some(team)).collect::<Vec<_>>();
+        if circle.len() % 2 == 1 {
+            circle.insert(0, None);
+        }
+        let count = circle.len();
+        let mut rounds = Vec::new();
+        for idx in 0..count.saturating_sub(1) {
+            let mut fixtures = Vec::new();
+            let mut bye = None;
+            for slot in 0..count / 2 {
+                let (mut home, mut away) = (circle[slot], circle[count - 1 - slot]);
+                if (slot == 0 && idx % 2 == 1) || slot % 2 == 1 {
+                    (home, away) = (away, home);
+                }
+                match (home, away) {
+                    (Some(home), Some(away)) => fixtures.push(Fixture {
+                        home: home.to_string(),
+                        away: away.to_string(),
+                    }),
+                    (Some(team), None) | (None, Some(team)) => bye = Some(team.to_string()),
+                    (None, None) => {}
+                }
+            }
+            rounds.push(Round {
+                number: idx + 1,
+                fixtures,
+                bye,
+            });
+            circle[1..].rotate_right(1);
+        }
+        Ok(rounds)
+    }
+
+    // The single round-robin followed by its return matches with the venues swapped.
+    pub fn double_round_robin(teams: &[&str]) -> Result<Vec<Round>, FixtureError> {
+        let first_half = round_robin(teams)?;
+        let second_half = first_half
+            .iter()
+            .map(|round| Round {
+                number: round.number + first_half.len(),
+                fixtures: round
+                    .fixtures
+                    .iter()
+                    .map(|fixture| Fixture {
+                        home: fixture.away.clone(),
+                        away: fixture.home.clone(),
+                    })
+                    .collect(),
+                bye: round.bye.clone(),
+            })
+            .collect::<Vec<_>>();
+        Ok(first_half.into_iter().chain(second_half).collect())
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone)]
+    enum Slot {
+        Team(String),
+        Bye,
+        Pending,
+    }
+
+    // A single-elimination bracket, the winners of each round meeting in
+    // the next one.
+    #[derive(Debug, PartialEq, Eq, Clone)]
+    pub struct Bracket {
+        // The slots of each round, pairs of neighbours playing each other.
+        rounds: Vec<Vec<Slot>>,
+    }
+
+    // The seeds in bracket order for a bracket of `size`, so that 1 and 2
+    // can only meet in the final: 1 v 8, 4 v 5, 2 v 7, 3 v 6 for 8.
+    fn seed_order(size: usize) -> Vec<usize> {
+        let mut order = vec![1];
+        while order.len() < size {
+            let total = order.len() * 2 + 1;
+            order = order
+                .iter()
+                .flat_map(|&seed| [seed, total - seed])
+                .collect();
+        }
+        order
+    }
+
+    impl Bracket {
+        // `seeds` strongest first. Missing places up to the next power of
+        // two are byes, which go to the top seeds.
+        pub fn new(seeds: &[&str]) -> Result<Self, FixtureError> {
+            check_teams(seeds)?;
+            let size = seeds.len().next_power_of_two();
+            let first = seed_order(size)
+                .into_iter()
+                .map(|seed| match seeds.get(seed - 1) {
+                    Some(team) => Slot::Team(team.to_string()),
+                    None => Slot::Bye,
+                })
+                .collect::<Vec<_>>();
+            let mut rounds = vec![first];
+            while rounds.last().unwrap().len() > 1 {
+                let next = rounds.last().unwrap().len() / 2;
+                rounds.push(vec![Slot::Pending; next]);
+            }
+            let mut bracket = Bracket { rounds };
+            for idx in 0..size / 2 {
+                let winner = match &bracket.rounds[0][idx * 2..idx * 2 + 2] {
+                    [Slot::Team(team), Slot::Bye] | [Slot::Bye, Slot::Team(team)] => team.clone(),
+                    _ => continue,
+                };
+                bracket.rounds[1][idx] = Slot::Team(winner);
+            }
+            Ok(bracket)
+        }
+
+        // The fixtures of the earliest round still being played, the
+        // higher placed team at home.
+        pub fn next_round(&self) -> Option<Round> {
+            self.rounds.windows(2).enumerate().find_map(|(idx, pair)| {
+                let fixtures = pair[0]
+                    .chunks(2)
+                    .zip(&pair[1])
+                    .filter_map(|(teams, winner)| match (teams, winner) {
+                        ([Slot::Team(home), Slot::Team(away)], Slot::Pending) => Some(Fixture {
+                            home: home.clone(),
+                            away: away.clone(),
+                        }),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                (!fixtures.is_empty()).then_some(Round {
+                    number: idx + 1,
+                    fixtures,
+                    bye: None,
+                })
+            })
+        }
+
+        // Advances the winners of `match_results`, in the `tally` format.
+        // Nothing is recorded if a line is not a decided bracket fixture.
+        pub fn record(&mut self, match_results: &str) -> Result<(), TallyError> {
+            let mut bracket = self.clone();
+            for (line, content) in lines(match_results) {
+                let error = |reason| TallyError {
+                    line,
+                    content: content.to_string(),
+                    reason,
+                };
+                let game = parse_match(content).map_err(error)?;
+                let winner = match game.outcome {
+                    Outcome::Win => game.first_team,
+                    Outcome::Loss => game.second_team,
+                    Outcome::Draw => return Err(error(Reason::DrawnKnockout)),
+                };
+                let slot = bracket.pending_slot(game.first_team, game.second_team);
+                let (round, idx) = slot.ok_or_else(|| error(Reason::NotAFixture))?;
+                bracket.rounds[round + 1][idx] = Slot::Team(winner.to_string());
+            }
+            *self = bracket;
+            Ok(())
+        }
+
+        // The round and place the winner of the match between `a` and `b`
+        // goes to, if that match is still to be played.
+        fn pending_slot(&self, a: &str, b: &str) -> Option<(usize, usize)> {
+            let is = |slot: &Slot, team: &str| slot == &Slot::Team(team.to_string());
+            self.rounds
+                .windows(2)
+                .enumerate()
+                .find_map(|(round, pair)| {
+                    pair[0]
+                        .chunks(2)
+                        .zip(&pair[1])
+                        .position(|(teams, winner)| {
+                            winner == &Slot::Pending
+                                && ((is(&teams[0], a) && is(&teams[1], b))
+                                    || (is(&teams[0], b) && is(&teams[1], a)))
+                        })
+                        .map(|idx| (round, idx))
+                })
+        }
+
+        pub fn champion(&self) -> Option<&str> {
+            match self.rounds.last()?.as_slice() {
+                [Slot::Team(team)] => Some(team),
+                _ => None,
+            }
+        }
+    }
+}
+
+pub mod swiss {
+    use std::collections::HashSet;
+
+    use crate::fixtures::{Fixture, FixtureError, Round};
+    use crate::{MatchId, Reason, Standings, TeamResult, Tournament};
+
+    // A Swiss-system event: each round players meet others on the same
+    // score whom they have not met yet, so a winner emerges in far fewer
+    // rounds than a round-robin needs. Home is white.
+    #[derive(Debug, Clone)]
+    pub struct Swiss {
+        // In seeding order, which breaks ties on score.
+        players: Vec<String>,
+        tournament: Tournament,
+        // The bye of each round paired so far, if there was one.
+        byes: Vec<Option<String>>,
+    }
+
+    // A player's record as far as pairing is concerned.
+    struct Entry {
+        score: u64,
+        opponents: HashSet<usize>,
+        // Home minus away games.
+        balance: i64,
+        last_home: Option<bool>,
+        had_bye: bool,
+    }
+
+    impl Swiss {
+        pub fn new(players: &[&str]) -> Result<Self, FixtureError> {
+            for (idx, player) in players.iter().enumerate() {
+                if players[..idx].contains(player) {
+                    return Err(FixtureError::DuplicateTeam(player.to_string()));
+                }
+            }
+            Ok(Swiss {
+                players: players.iter().map(|player| player.to_string()).collect(),
+                tournament: Tournament::new(),
+                byes: Vec::new(),
+            })
+        }
+
+        pub fn with_tournament(mut self, tournament: Tournament) -> Self {
+            self.tournament = tournament;
+            self
+        }
+
+        // Records a result in the `tally` format, the home player being white.
+        pub fn record(&mut self, result: &str) -> Result<MatchId, Reason> {
+            self.tournament.record(result)
+        }
+
+        pub fn remove(&mut self, id: MatchId) -> bool {
+            self.tournament.remove(id)
+        }
+
+        // The standings with the points of a win added for each bye, which
+        // does not count as a match played.
+        pub fn standings(&self) -> Standings {
+            let mut rows = self.tournament.teams.clone();
+            for bye in self.byes.iter().flatten() {
+                rows.entry(bye.clone())
+                    .or_insert_with(|| TeamResult::new_empty(bye.clone()))
+                    .points += self.tournament.rules.win;
+            }
+            self.tournament.order(rows.into_values().collect())
+        }
+
+        // Points from the standings, a bye counting as a win.
+        pub fn score(&self, player: &str) -> u64 {
+            let points = self
+                .tournament
+                .teams
+                .get(player)
+                .map_or(0, |row| row.points);
+            let byes = self
+                .byes
+                .iter()
+                .flatten()
+                .filter(|bye| *bye == player)
+                .count() as u64;
+            points + byes * self.tournament.rules.win
+        }
+
+        // Takes back the last round paired, with its bye, if it is abandoned
+        // before being played. Results recorded for it must be removed first.
+        // Returns false if no round was paired.
+        pub fn abandon_round(&mut self) -> bool {
+            self.byes.pop().is_some()
+        }
+
+        // Pairs the next round from the results recorded so far, giving the
+        // bye, if any, to the lowest ranked player yet to have one.
+        pub fn pair_round(&mut self) -> Round {
+            let entries = self.entries();
+            let mut ranking = (0..self.players.len()).collect::<Vec<_>>();
+            ranking.sort_by_key(|&idx| std::cmp::Reverse(entries[idx].score));
+
+            let mut bye_candidates = Vec::new();
+            if ranking.len() % 2 == 1 {
+                bye_candidates = ranking.iter().rev().copied().collect();
+                bye_candidates.sort_by_key(|&idx| entries[idx].had_bye);
+            }
+            let (bye, pairs) = self.pairing(&ranking, &entries, &bye_candidates);
+
+            self.byes.push(bye.map(|bye| self.players[bye].clone()));
+            Round {
+                number: self.byes.len(),
+                fixtures: pairs
+                    .into_iter()
+                    .map(|(a, b)| {
+                        let (home, away) = self.colours(a, b, &entries);
+                        Fixture {
+                            home: self.players[home].clone(),
+                            away: self.players[away].clone(),
+                        }
+                    })
+                    .collect(),
+                bye: bye.map(|bye| self.players[bye].clone()),
+            }
+        }
+
+        fn entries(&self) -> Vec<Entry> {
+            let index = |name: &str| self.players.iter().position(|player| player == name);
+            let mut entries = self
+                .players
+                .iter()
+                .map(|player| Entry {
+                    score: self.score(player),
+                    opponents: HashSet::new(),
+                    balance: 0,
+                    last_home: None,
+                    had_bye: self.byes.iter().flatten().any(|bye| bye == player),
+                })
+                .collect::<Vec<_>>();
+            for recorded in self.tournament.recorded() {
+                let (Some(home), Some(away)) = (index(&recorded.home), index(&recorded.away))
+                else {
+                    continue;
+                };
+                entries[home].opponents.insert(away);
+                entries[away].opponents.insert(home);
+                entries[home].balance += 1;
+                entries[away].balance -= 1;
+                entries[home].last_home = Some(true);
+                entries[away].last_home = Some(false);
+            }
+            entries
+        }
+
+        // The bye and pairs for the round, avoiding rematches if at all possible.
+        fn pairing(
+            &self,
+            ranking: &[usize],
+            entries: &[Entry],
+            bye_candidates: &[usize],
+        ) -> (Option<usize>, Vec<(usize, usize)>) {
+            for allow_rematch in [false, true] {
+                // Sets of players already found impossible to pair, in
+                // ranking order, shared by the choices of bye.
+                let mut unpairable = HashSet::new();
+                let byes = if bye_candidates.is_empty() {
+                    vec![None]
+                } else {
+                    bye_candidates.iter().map(|&idx| Some(idx)).collect()
+                };
+                for bye in byes {
+                    let rest = ranking
+                        .iter()
+                        .copied()
+                        .filter(|&idx| Some(idx) != bye)
+                        .collect::<Vec<_>>();
+                    if let Some(pairs) = pair(&rest, entries, allow_rematch, &mut unpairable) {
+                        return (bye, pairs);
                     }
-                    _ => panic!("Invalid value for verdict: {}", verdict),
                 }
-                first_team_stats.played += 1;
-                second_team_stats.played += 1;
-                results.insert(first_team, first_team_stats);
-                results.insert(second_team, second_team_stats);
             }
-            _ => panic!("The input is not in the expected format"),
+            unreachable!("an even number of players can always be paired with rematches")
+        }
+
+        // Gives home to the player who has had it less, then to the one who
+        // was away last, then to the higher ranked in odd rounds and the
+        // lower ranked in even ones. `byes` already counts this round.
+        fn colours(&self, a: usize, b: usize, entries: &[Entry]) -> (usize, usize) {
+            let (first, second) = (&entries[a], &entries[b]);
+            let a_home = match first.balance.cmp(&second.balance) {
+                std::cmp::Ordering::Less => true,
+                std::cmp::Ordering::Greater => false,
+                std::cmp::Ordering::Equal => match (first.last_home, second.last_home) {
+                    (Some(false), Some(true)) => true,
+                    (Some(true), Some(false)) => false,
+                    _ => self.byes.len() % 2 == 1,
+                },
+            };
+            if a_home {
+                (a, b)
+            } else {
+                (b, a)
+            }
         }
     }
-    let mut results = results.into_values().collect::<Vec<_>>();
-    results.sort_by(|a, b| {
-        if a.points != b.points {
-            a.points.cmp(&b.points).reverse()
-        } else {
-            a.team_name.cmp(&b.team_name)
+
+    // Pairs `ranking` from the top, each player against the player half way
+    // down the rest of their score group, then the other players of the
+    // group, then those on lower scores, backtracking when a choice leaves
+    // the rest unpairable. Remembering the sets of players that could not
+    // be paired, and giving up at once when one of them has no possible
+    // opponent left, keeps the search from trying every ordering in vain.
+    fn pair(
+        ranking: &[usize],
+        entries: &[Entry],
+        allow_rematch: bool,
+        unpairable: &mut HashSet<Vec<usize>>,
+    ) -> Option<Vec<(usize, usize)>> {
+        let Some((&first, rest)) = ranking.split_first() else {
+            return Some(Vec::new());
+        };
+        let can_meet = |a: usize, b: usize| allow_rematch || !entries[a].opponents.contains(&b);
+        let stranded = ranking
+            .iter()
+            .any(|&a| !ranking.iter().any(|&b| a != b && can_meet(a, b)));
+        if stranded || unpairable.contains(ranking) {
+            return None;
         }
-    });
-    format_output(&results)
+        let group = rest
+            .iter()
+            .take_while(|&&idx| entries[idx].score == entries[first].score)
+            .count();
+        let (same, lower) = rest.split_at(group);
+        let candidates = same[group / 2..]
+            .iter()
+            .chain(&same[..group / 2])
+            .chain(lower);
+        for &opponent in candidates {
+            if !can_meet(first, opponent) {
+                continue;
+            }
+            let remaining = rest
+                .iter()
+                .copied()
+                .filter(|&idx| idx != opponent)
+                .collect::<Vec<_>>();
+            if let Some(mut pairs) = pair(&remaining, entries, allow_rematch, unpairable) {
+                pairs.insert(0, (first, opponent));
+                return Some(pairs);
+            }
+        }
+        unpairable.insert(ranking.to_vec());
+        None
+    }
 }
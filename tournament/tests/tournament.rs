@@ -140,3 +140,801 @@ fn ties_broken_alphabetically() {
 
     assert_eq!(tournament::tally(&input), expected);
 }
+
+#[test]
+fn try_tally_reports_the_first_bad_line() {
+    let input = "Allegoric Alaskans;Blithering Badgers;win\n".to_string()
+        + "Devastating Donkeys;Courageous Californians;draw;extra\n"
+        + "Devastating Donkeys;Allegoric Alaskans;lose";
+    let error = tournament::try_tally(&input).unwrap_err();
+
+    assert_eq!(error.line, 2);
+    assert_eq!(
+        error.content,
+        "Devastating Donkeys;Courageous Californians;draw;extra"
+    );
+    assert_eq!(error.reason, tournament::Reason::WrongFieldCount(4));
+}
+
+#[test]
+fn try_tally_rejects_unknown_verdicts_and_empty_team_names() {
+    let error = tournament::try_tally("Allegoric Alaskans;Blithering Badgers;lose").unwrap_err();
+    assert_eq!(
+        error.reason,
+        tournament::Reason::UnknownVerdict("lose".to_string())
+    );
+
+    let error = tournament::try_tally(";Blithering Badgers;win").unwrap_err();
+    assert_eq!(error.reason, tournament::Reason::EmptyTeamName);
+
+    let error = tournament::try_tally("Blithering Badgers;Blithering Badgers;win").unwrap_err();
+    assert_eq!(error.reason, tournament::Reason::SameTeam);
+}
+
+#[test]
+fn try_tally_matches_tally_on_valid_input() {
+    let input = "Allegoric Alaskans;Blithering Badgers;win\n".to_string()
+        + "Devastating Donkeys;Courageous Californians;draw";
+
+    assert_eq!(tournament::try_tally(&input), Ok(tournament::tally(&input)));
+}
+
+#[test]
+fn blank_lines_are_skipped() {
+    let input = "Allegoric Alaskans;Blithering Badgers;win\n\n   \n".to_string()
+        + "Allegoric Alaskans;Blithering Badgers;loss\n";
+    let expected = "".to_string()
+        + "Team                           | MP |  W |  D |  L |  P\n"
+        + "Allegoric Alaskans             |  2 |  1 |  0 |  1 |  3\n"
+        + "Blithering Badgers             |  2 |  1 |  0 |  1 |  3";
+
+    assert_eq!(tournament::try_tally(&input), Ok(expected));
+}
+
+#[test]
+fn lenient_tally_skips_and_returns_bad_lines() {
+    let input = "Allegoric Alaskans;Blithering Badgers;win\n".to_string()
+        + "this line is not a result\n"
+        + "Blithering Badgers;Allegoric Alaskans;lost";
+    let expected = "".to_string()
+        + "Team                           | MP |  W |  D |  L |  P\n"
+        + "Allegoric Alaskans             |  1 |  1 |  0 |  0 |  3\n"
+        + "Blithering Badgers             |  1 |  0 |  0 |  1 |  0";
+    let (table, errors) = tournament::tally_lenient(&input);
+
+    assert_eq!(table, expected);
+    assert_eq!(
+        errors,
+        vec![
+            tournament::TallyError {
+                line: 2,
+                content: "this line is not a result".to_string(),
+                reason: tournament::Reason::WrongFieldCount(1),
+            },
+            tournament::TallyError {
+                line: 3,
+                content: "Blithering Badgers;Allegoric Alaskans;lost".to_string(),
+                reason: tournament::Reason::UnknownVerdict("lost".to_string()),
+            },
+        ]
+    );
+}
+
+#[test]
+#[should_panic]
+fn tally_still_panics_on_bad_input() {
+    tournament::tally("Allegoric Alaskans;Blithering Badgers");
+}
+
+#[test]
+fn scored_results_count_as_wins_draws_and_losses() {
+    let input = "Allegoric Alaskans;Blithering Badgers;3-1\n".to_string()
+        + "Blithering Badgers;Courageous Californians;2-2\n"
+        + "Courageous Californians;Allegoric Alaskans;1-0";
+    let expected = "".to_string()
+        + "Team                           | MP |  W |  D |  L |  P\n"
+        + "Courageous Californians        |  2 |  1 |  1 |  0 |  4\n"
+        + "Allegoric Alaskans             |  2 |  1 |  0 |  1 |  3\n"
+        + "Blithering Badgers             |  2 |  0 |  1 |  1 |  1";
+
+    assert_eq!(tournament::tally(&input), expected);
+}
+
+#[test]
+fn malformed_scores_are_reported() {
+    let error = tournament::try_tally("Allegoric Alaskans;Blithering Badgers;3-x").unwrap_err();
+
+    assert_eq!(
+        error.reason,
+        tournament::Reason::InvalidScore("3-x".to_string())
+    );
+}
+
+#[test]
+fn custom_points_per_result() {
+    let input = "Allegoric Alaskans;Blithering Badgers;win\n".to_string()
+        + "Blithering Badgers;Courageous Californians;draw\n"
+        + "Courageous Californians;Allegoric Alaskans;draw";
+    let expected = "".to_string()
+        + "Team                           | MP |  W |  D |  L |  P\n"
+        + "Allegoric Alaskans             |  2 |  1 |  1 |  0 |  3\n"
+        + "Courageous Californians        |  2 |  0 |  2 |  0 |  2\n"
+        + "Blithering Badgers             |  2 |  0 |  1 |  1 |  1";
+    let rules = tournament::Rules::default().with_points(2, 1, 0);
+
+    assert_eq!(rules.tally(&input), expected);
+}
+
+#[test]
+fn ties_broken_by_goal_difference_then_goals_scored() {
+    let input = "Allegoric Alaskans;Devastating Donkeys;1-0\n".to_string()
+        + "Blithering Badgers;Devastating Donkeys;3-1\n"
+        + "Courageous Californians;Devastating Donkeys;4-2";
+    let expected = "".to_string()
+        + "Team                           | MP |  W |  D |  L |  P\n"
+        + "Courageous Californians        |  1 |  1 |  0 |  0 |  3\n"
+        + "Blithering Badgers             |  1 |  1 |  0 |  0 |  3\n"
+        + "Allegoric Alaskans             |  1 |  1 |  0 |  0 |  3\n"
+        + "Devastating Donkeys            |  3 |  0 |  0 |  3 |  0";
+    let rules = tournament::Rules::default().with_tie_breakers(&[
+        tournament::TieBreaker::GoalDifference,
+        tournament::TieBreaker::GoalsScored,
+    ]);
+
+    assert_eq!(rules.tally(&input), expected);
+}
+
+#[test]
+fn ties_broken_head_to_head() {
+    let input = "Allegoric Alaskans;Blithering Badgers;0-1\n".to_string()
+        + "Allegoric Alaskans;Courageous Californians;5-0";
+    let rules = tournament::Rules::default().with_tie_breakers(&[
+        tournament::TieBreaker::HeadToHead,
+        tournament::TieBreaker::GoalDifference,
+    ]);
+    let expected = "".to_string()
+        + "Team                           | MP |  W |  D |  L |  P\n"
+        + "Blithering Badgers             |  1 |  1 |  0 |  0 |  3\n"
+        + "Allegoric Alaskans             |  2 |  1 |  0 |  1 |  3\n"
+        + "Courageous Californians        |  1 |  0 |  0 |  1 |  0";
+
+    assert_eq!(rules.tally(&input), expected);
+}
+
+#[test]
+fn tie_breakers_apply_in_order() {
+    let input = "Allegoric Alaskans;Blithering Badgers;0-1\n".to_string()
+        + "Allegoric Alaskans;Courageous Californians;5-0";
+    let rules = tournament::Rules::default().with_tie_breakers(&[
+        tournament::TieBreaker::GoalDifference,
+        tournament::TieBreaker::HeadToHead,
+    ]);
+    let expected = "".to_string()
+        + "Team                           | MP |  W |  D |  L |  P\n"
+        + "Allegoric Alaskans             |  2 |  1 |  0 |  1 |  3\n"
+        + "Blithering Badgers             |  1 |  1 |  0 |  0 |  3\n"
+        + "Courageous Californians        |  1 |  0 |  0 |  1 |  0";
+
+    assert_eq!(rules.tally(&input), expected);
+}
+
+#[test]
+fn teams_level_on_every_tie_breaker_are_ordered_by_name() {
+    let input = "Courageous Californians;Blithering Badgers;1-0\n".to_string()
+        + "Blithering Badgers;Allegoric Alaskans;1-0\n"
+        + "Allegoric Alaskans;Courageous Californians;1-0";
+    let rules = tournament::Rules::default().with_tie_breakers(&[
+        tournament::TieBreaker::HeadToHead,
+        tournament::TieBreaker::GoalDifference,
+        tournament::TieBreaker::GoalsScored,
+    ]);
+    let expected = "".to_string()
+        + "Team                           | MP |  W |  D |  L |  P\n"
+        + "Allegoric Alaskans             |  2 |  1 |  0 |  1 |  3\n"
+        + "Blithering Badgers             |  2 |  1 |  0 |  1 |  3\n"
+        + "Courageous Californians        |  2 |  1 |  0 |  1 |  3";
+
+    assert_eq!(rules.tally(&input), expected);
+}
+
+#[test]
+fn standings_expose_per_team_rows() {
+    let input = "Allegoric Alaskans;Blithering Badgers;3-1\n".to_string()
+        + "Blithering Badgers;Courageous Californians;draw";
+    let standings = tournament::try_standings(&input).unwrap();
+    let names = standings
+        .rows()
+        .iter()
+        .map(|row| row.team_name.as_str())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        names,
+        [
+            "Allegoric Alaskans",
+            "Blithering Badgers",
+            "Courageous Californians"
+        ]
+    );
+    assert_eq!(
+        standings.team("Blithering Badgers"),
+        Some(&tournament::TeamResult {
+            team_name: "Blithering Badgers".to_string(),
+            played: 2,
+            points: 1,
+            won: 0,
+            drawn: 1,
+            lost: 1,
+            goals_for: 1,
+            goals_against: 3,
+        })
+    );
+    assert_eq!(standings.rows()[0].goal_difference(), 2);
+    assert_eq!(standings.to_text(), tournament::tally(&input));
+}
+
+#[test]
+fn text_table_widens_for_long_team_names() {
+    let input = "The Extraordinarily Long-Named Club;Blithering Badgers;win";
+    let expected = "".to_string()
+        + "Team                                | MP |  W |  D |  L |  P\n"
+        + "The Extraordinarily Long-Named Club |  1 |  1 |  0 |  0 |  3\n"
+        + "Blithering Badgers                  |  1 |  0 |  0 |  1 |  0";
+
+    assert_eq!(tournament::tally(input), expected);
+}
+
+#[test]
+fn text_table_aligns_accented_and_wide_team_names() {
+    let input = "Atlético Álamos;東京ユナイテッド;win";
+    let expected = "".to_string()
+        + "Team                           | MP |  W |  D |  L |  P\n"
+        + "Atlético Álamos                |  1 |  1 |  0 |  0 |  3\n"
+        + "東京ユナイテッド               |  1 |  0 |  0 |  1 |  0";
+
+    assert_eq!(tournament::tally(input), expected);
+}
+
+#[test]
+fn standings_as_csv() {
+    let input = "Allegoric Alaskans;Blithering Badgers, Inc.;3-1";
+    let expected = "".to_string()
+        + "Team,MP,W,D,L,GF,GA,GD,P\n"
+        + "Allegoric Alaskans,1,1,0,0,3,1,2,3\n"
+        + "\"Blithering Badgers, Inc.\",1,0,0,1,1,3,-2,0";
+
+    assert_eq!(tournament::try_standings(input).unwrap().to_csv(), expected);
+}
+
+#[test]
+fn standings_as_json() {
+    let input = "Allegoric \"AA\" Alaskans;Blithering Badgers;1-1";
+    let expected = "".to_string()
+        + "[\n"
+        + "  {\"team\": \"Allegoric \\\"AA\\\" Alaskans\", \"played\": 1, \"won\": 0, \"drawn\": 1, \"lost\": 0, \"goals_for\": 1, \"goals_against\": 1, \"goal_difference\": 0, \"points\": 1},\n"
+        + "  {\"team\": \"Blithering Badgers\", \"played\": 1, \"won\": 0, \"drawn\": 1, \"lost\": 0, \"goals_for\": 1, \"goals_against\": 1, \"goal_difference\": 0, \"points\": 1}\n"
+        + "]";
+
+    assert_eq!(
+        tournament::try_standings(input).unwrap().to_json(),
+        expected
+    );
+    assert_eq!(tournament::try_standings("").unwrap().to_json(), "[]");
+}
+
+#[test]
+fn standings_as_markdown() {
+    let input = "Allegoric Alaskans;東京|FC;win";
+    let expected = "".to_string()
+        + "| Team               | MP |  W |  D |  L |  P |\n"
+        + "|:-------------------|---:|---:|---:|---:|---:|\n"
+        + "| Allegoric Alaskans |  1 |  1 |  0 |  0 |  3 |\n"
+        + "| 東京\\|FC           |  1 |  0 |  0 |  1 |  0 |";
+
+    assert_eq!(
+        tournament::try_standings(input).unwrap().to_markdown(),
+        expected
+    );
+}
+
+#[test]
+fn standings_as_html() {
+    let input = "Allegoric & Alaskans;<Blithering Badgers>;loss";
+    let expected = "".to_string()
+        + "<table>\n"
+        + "  <thead>\n"
+        + "    <tr><th>Team</th><th>MP</th><th>W</th><th>D</th><th>L</th><th>P</th></tr>\n"
+        + "  </thead>\n"
+        + "  <tbody>\n"
+        + "    <tr><td>&lt;Blithering Badgers&gt;</td><td>1</td><td>1</td><td>0</td><td>0</td><td>3</td></tr>\n"
+        + "    <tr><td>Allegoric &amp; Alaskans</td><td>1</td><td>0</td><td>0</td><td>1</td><td>0</td></tr>\n"
+        + "  </tbody>\n"
+        + "</table>";
+
+    assert_eq!(
+        tournament::try_standings(input).unwrap().to_html(),
+        expected
+    );
+}
+
+fn fixture(home: &str, away: &str) -> tournament::fixtures::Fixture {
+    tournament::fixtures::Fixture {
+        home: home.to_string(),
+        away: away.to_string(),
+    }
+}
+
+#[test]
+fn round_robin_with_even_count() {
+    let rounds = tournament::fixtures::round_robin(&["A", "B", "C", "D"]).unwrap();
+    let fixtures = rounds
+        .iter()
+        .map(|round| round.fixtures.clone())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        fixtures,
+        vec![
+            vec![fixture("A", "D"), fixture("C", "B")],
+            vec![fixture("C", "A"), fixture("B", "D")],
+            vec![fixture("A", "B"), fixture("D", "C")],
+        ]
+    );
+    assert!(rounds.iter().all(|round| round.bye.is_none()));
+    assert_eq!(
+        rounds.iter().map(|round| round.number).collect::<Vec<_>>(),
+        [1, 2, 3]
+    );
+}
+
+#[test]
+fn round_robin_gives_a_bye_each_round_for_odd_counts() {
+    let rounds = tournament::fixtures::round_robin(&["A", "B", "C", "D", "E"]).unwrap();
+    let mut byes = rounds
+        .iter()
+        .map(|round| round.bye.clone().unwrap())
+        .collect::<Vec<_>>();
+    byes.sort();
+
+    assert_eq!(rounds.len(), 5);
+    assert!(rounds.iter().all(|round| round.fixtures.len() == 2));
+    assert_eq!(byes, ["A", "B", "C", "D", "E"]);
+}
+
+#[test]
+fn round_robin_pairs_everyone_once_with_balanced_venues() {
+    for count in 2..=12 {
+        let names = (0..count).map(|idx| format!("T{idx}")).collect::<Vec<_>>();
+        let teams = names.iter().map(String::as_str).collect::<Vec<_>>();
+        let fixtures = tournament::fixtures::round_robin(&teams)
+            .unwrap()
+            .into_iter()
+            .flat_map(|round| round.fixtures)
+            .collect::<Vec<_>>();
+        let mut pairs = fixtures
+            .iter()
+            .map(|fixture| {
+                let mut pair = [fixture.home.clone(), fixture.away.clone()];
+                pair.sort();
+                pair
+            })
+            .collect::<Vec<_>>();
+        pairs.sort();
+        pairs.dedup();
+
+        assert_eq!(pairs.len(), count * (count - 1) / 2);
+        assert_eq!(fixtures.len(), pairs.len());
+        for team in &names {
+            let home = fixtures.iter().filter(|f| &f.home == team).count() as i64;
+            let away = fixtures.iter().filter(|f| &f.away == team).count() as i64;
+            assert!(
+                (home - away).abs() <= 1,
+                "{team} of {count}: {home} v {away}"
+            );
+            let venues = fixtures
+                .iter()
+                .filter(|f| &f.home == team || &f.away == team)
+                .map(|f| &f.home == team)
+                .collect::<Vec<_>>();
+            let longest = venues
+                .chunk_by(|a, b| a == b)
+                .map(|run| run.len())
+                .max()
+                .unwrap_or(0);
+            let bound = if count % 2 == 0 { 2 } else { 1 };
+            assert!(
+                longest <= bound,
+                "{team} of {count}: {longest} in a row at one venue"
+            );
+        }
+    }
+}
+
+#[test]
+fn double_round_robin_swaps_venues() {
+    let rounds = tournament::fixtures::double_round_robin(&["A", "B", "C"]).unwrap();
+
+    assert_eq!(rounds.len(), 6);
+    for (first, second) in rounds[..3].iter().zip(&rounds[3..]) {
+        assert_eq!(second.number, first.number + 3);
+        assert_eq!(second.bye, first.bye);
+        for (a, b) in first.fixtures.iter().zip(&second.fixtures) {
+            assert_eq!((&a.home, &a.away), (&b.away, &b.home));
+        }
+    }
+}
+
+#[test]
+fn fixtures_reject_duplicate_teams() {
+    assert_eq!(
+        tournament::fixtures::round_robin(&["A", "B", "A"]),
+        Err(tournament::fixtures::FixtureError::DuplicateTeam(
+            "A".to_string()
+        ))
+    );
+    assert!(tournament::fixtures::Bracket::new(&["A", "A"]).is_err());
+}
+
+#[test]
+fn fixtures_need_at_least_two_teams() {
+    use tournament::fixtures::{double_round_robin, round_robin, Bracket, FixtureError};
+
+    assert_eq!(round_robin(&["A"]), Err(FixtureError::TooFewTeams));
+    assert_eq!(round_robin(&[]), Err(FixtureError::TooFewTeams));
+    assert_eq!(double_round_robin(&["A"]), Err(FixtureError::TooFewTeams));
+    assert_eq!(Bracket::new(&["A"]), Err(FixtureError::TooFewTeams));
+    assert_eq!(round_robin(&["A", "B"]).unwrap().len(), 1);
+}
+
+#[test]
+fn fixtures_feed_back_into_the_tally() {
+    let rounds = tournament::fixtures::round_robin(&["A", "B", "C"]).unwrap();
+    let input = rounds
+        .iter()
+        .flat_map(|round| &round.fixtures)
+        .map(|fixture| fixture.result_line("2-1"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let standings = tournament::try_standings(&input).unwrap();
+
+    // Each team is at home once, and the home side always wins.
+    assert!(standings
+        .rows()
+        .iter()
+        .all(|row| row.played == 2 && row.won == 1 && row.lost == 1));
+    assert_eq!(standings.rows().len(), 3);
+    assert_eq!(
+        standings.rows().iter().map(|row| row.points).sum::<u64>(),
+        9
+    );
+}
+
+#[test]
+fn seeded_bracket_keeps_top_seeds_apart() {
+    let bracket =
+        tournament::fixtures::Bracket::new(&["S1", "S2", "S3", "S4", "S5", "S6", "S7", "S8"])
+            .unwrap();
+    let round = bracket.next_round().unwrap();
+
+    assert_eq!(round.number, 1);
+    assert_eq!(
+        round.fixtures,
+        vec![
+            fixture("S1", "S8"),
+            fixture("S4", "S5"),
+            fixture("S2", "S7"),
+            fixture("S3", "S6"),
+        ]
+    );
+    assert_eq!(bracket.champion(), None);
+}
+
+#[test]
+fn bracket_gives_byes_to_top_seeds() {
+    let bracket = tournament::fixtures::Bracket::new(&["S1", "S2", "S3", "S4", "S5"]).unwrap();
+    let round = bracket.next_round().unwrap();
+
+    assert_eq!(round.number, 1);
+    assert_eq!(round.fixtures, vec![fixture("S4", "S5")]);
+}
+
+#[test]
+fn bracket_advances_recorded_winners_to_a_champion() {
+    let mut bracket = tournament::fixtures::Bracket::new(&["S1", "S2", "S3", "S4", "S5"]).unwrap();
+    bracket.record("S4;S5;1-2").unwrap();
+
+    let round = bracket.next_round().unwrap();
+    assert_eq!(round.number, 2);
+    assert_eq!(
+        round.fixtures,
+        vec![fixture("S1", "S5"), fixture("S2", "S3")]
+    );
+
+    let results = round
+        .fixtures
+        .iter()
+        .map(|fixture| fixture.result_line("win"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    bracket.record(&results).unwrap();
+    bracket.record("S2;S1;loss").unwrap();
+
+    assert_eq!(bracket.next_round(), None);
+    assert_eq!(bracket.champion(), Some("S1"));
+}
+
+#[test]
+fn bracket_rejects_draws_and_unknown_matches() {
+    let mut bracket = tournament::fixtures::Bracket::new(&["S1", "S2", "S3", "S4"]).unwrap();
+
+    assert_eq!(
+        bracket.record("S1;S4;draw").unwrap_err().reason,
+        tournament::Reason::DrawnKnockout
+    );
+    let error = bracket.record("S1;S4;win\n\nS1;S2;win").unwrap_err();
+    assert_eq!(error.line, 3);
+    assert_eq!(error.reason, tournament::Reason::NotAFixture);
+    // The failed batch is not recorded in part.
+    assert_eq!(bracket.next_round().unwrap().fixtures.len(), 2);
+    bracket.record("S1;S4;win").unwrap();
+    assert_eq!(bracket.next_round().unwrap().fixtures.len(), 1);
+}
+
+#[test]
+fn tournament_standings_match_the_tally() {
+    let lines = [
+        "Allegoric Alaskans;Blithering Badgers;win",
+        "Devastating Donkeys;Courageous Californians;draw",
+        "Devastating Donkeys;Allegoric Alaskans;win",
+        "Courageous Californians;Blithering Badgers;loss",
+        "Blithering Badgers;Devastating Donkeys;loss",
+        "Allegoric Alaskans;Courageous Californians;win",
+    ];
+    let mut tournament = tournament::Tournament::new();
+    for line in lines {
+        tournament.record(line).unwrap();
+    }
+
+    assert_eq!(
+        tournament.standings().to_text(),
+        tournament::tally(&lines.join("\n"))
+    );
+}
+
+#[test]
+fn tournament_rejects_malformed_results() {
+    let mut tournament = tournament::Tournament::new();
+    assert_eq!(
+        tournament.record("Allegoric Alaskans;win"),
+        Err(tournament::Reason::WrongFieldCount(2))
+    );
+    assert!(tournament.standings().rows().is_empty());
+}
+
+#[test]
+fn tournament_removes_mistaken_results() {
+    let mut tournament = tournament::Tournament::new();
+    tournament
+        .record("Allegoric Alaskans;Blithering Badgers;2-0")
+        .unwrap();
+    let mistake = tournament
+        .record("Blithering Badgers;Courageous Californians;1-0")
+        .unwrap();
+    let before = tournament.clone();
+    let correction = tournament
+        .record("Blithering Badgers;Allegoric Alaskans;0-1")
+        .unwrap();
+
+    assert!(tournament.remove(correction));
+    assert!(!tournament.remove(correction));
+    assert_eq!(tournament.standings(), before.standings());
+    assert_eq!(tournament.ratings(), before.ratings());
+
+    assert!(tournament.remove(mistake));
+    assert_eq!(tournament.standings().team("Courageous Californians"), None);
+    assert_eq!(tournament.rating("Courageous Californians"), None);
+    assert_eq!(
+        tournament.standings().to_text(),
+        tournament::tally("Allegoric Alaskans;Blithering Badgers;2-0")
+    );
+}
+
+#[test]
+fn elo_ratings_move_towards_the_result() {
+    let mut tournament = tournament::Tournament::new();
+    tournament
+        .record("Allegoric Alaskans;Blithering Badgers;win")
+        .unwrap();
+
+    assert_eq!(tournament.rating("Allegoric Alaskans"), Some(1516.0));
+    assert_eq!(tournament.rating("Blithering Badgers"), Some(1484.0));
+    assert_eq!(
+        tournament.ratings(),
+        [
+            ("Allegoric Alaskans", 1516.0),
+            ("Blithering Badgers", 1484.0)
+        ]
+    );
+}
+
+#[test]
+fn elo_with_k_factor_and_home_advantage() {
+    let elo = tournament::Elo::default()
+        .with_k_factor(20.0)
+        .with_home_advantage(400.0)
+        .with_initial_rating(1000.0);
+    let mut tournament = tournament::Tournament::new().with_elo(elo);
+    tournament
+        .record("Allegoric Alaskans;Blithering Badgers;draw")
+        .unwrap();
+
+    // The home side was expected to score 10/11, so a draw costs it 20 * (10/11 - 1/2).
+    let change = 20.0 * (10.0 / 11.0 - 0.5);
+    let home = tournament.rating("Allegoric Alaskans").unwrap();
+    let away = tournament.rating("Blithering Badgers").unwrap();
+    assert!((home - (1000.0 - change)).abs() < 1e-9);
+    assert!((away - (1000.0 + change)).abs() < 1e-9);
+}
+
+#[test]
+fn tournament_uses_its_rules() {
+    let rules = tournament::Rules::default()
+        .with_points(2, 1, 0)
+        .with_tie_breakers(&[tournament::TieBreaker::HeadToHead]);
+    let mut tournament = tournament::Tournament::new();
+    tournament
+        .record("Allegoric Alaskans;Blithering Badgers;0-1")
+        .unwrap();
+    tournament
+        .record("Allegoric Alaskans;Courageous Californians;5-0")
+        .unwrap();
+    let tournament = tournament.with_rules(rules);
+    let standings = tournament.standings();
+
+    assert_eq!(standings.rows()[0].team_name, "Blithering Badgers");
+    assert_eq!(standings.rows()[0].points, 2);
+    assert_eq!(standings.rows()[1].team_name, "Allegoric Alaskans");
+}
+
+fn pairs(round: &tournament::fixtures::Round) -> Vec<(&str, &str)> {
+    round
+        .fixtures
+        .iter()
+        .map(|fixture| (fixture.home.as_str(), fixture.away.as_str()))
+        .collect()
+}
+
+#[test]
+fn swiss_first_round_pairs_top_half_against_bottom_half() {
+    let mut swiss =
+        tournament::swiss::Swiss::new(&["P1", "P2", "P3", "P4", "P5", "P6", "P7", "P8"]).unwrap();
+    let round = swiss.pair_round();
+
+    assert_eq!(round.number, 1);
+    assert_eq!(round.bye, None);
+    assert_eq!(
+        pairs(&round),
+        [("P1", "P5"), ("P2", "P6"), ("P3", "P7"), ("P4", "P8")]
+    );
+}
+
+#[test]
+fn swiss_pairs_equal_scores_and_balances_colours() {
+    let mut swiss =
+        tournament::swiss::Swiss::new(&["P1", "P2", "P3", "P4", "P5", "P6", "P7", "P8"]).unwrap();
+    for fixture in swiss.pair_round().fixtures {
+        swiss.record(&fixture.result_line("win")).unwrap();
+    }
+    let round = swiss.pair_round();
+
+    // The winners meet the winners, each having had white, so the lower
+    // ranked, who were not at home yet either, get white in the other group.
+    assert_eq!(round.number, 2);
+    assert_eq!(
+        pairs(&round),
+        [("P3", "P1"), ("P4", "P2"), ("P7", "P5"), ("P8", "P6")]
+    );
+}
+
+#[test]
+fn swiss_gives_byes_to_the_lowest_ranked_without_one() {
+    let mut swiss = tournament::swiss::Swiss::new(&["P1", "P2", "P3", "P4", "P5"]).unwrap();
+    let first = swiss.pair_round();
+    assert_eq!(first.bye.as_deref(), Some("P5"));
+    assert_eq!(pairs(&first), [("P1", "P3"), ("P2", "P4")]);
+    for fixture in &first.fixtures {
+        swiss.record(&fixture.result_line("loss")).unwrap();
+    }
+    assert_eq!(swiss.score("P5"), 3);
+    let row = swiss.standings().team("P5").cloned().unwrap();
+    assert_eq!((row.played, row.points), (0, 3));
+
+    let second = swiss.pair_round();
+    assert_eq!(second.bye.as_deref(), Some("P2"));
+    assert!(pairs(&second)
+        .iter()
+        .any(|&(home, away)| home == "P5" || away == "P5"));
+}
+
+#[test]
+fn swiss_avoids_rematches_and_is_deterministic() {
+    let players = ["P1", "P2", "P3", "P4", "P5", "P6", "P7"];
+    let run = || {
+        let mut swiss = tournament::swiss::Swiss::new(&players).unwrap();
+        let mut rounds = Vec::new();
+        for idx in 0..5 {
+            let round = swiss.pair_round();
+            for (n, fixture) in round.fixtures.iter().enumerate() {
+                let verdict = ["win", "draw", "loss"][(idx + n) % 3];
+                swiss.record(&fixture.result_line(verdict)).unwrap();
+            }
+            rounds.push(round);
+        }
+        rounds
+    };
+    let rounds = run();
+
+    let mut met = std::collections::HashSet::new();
+    for round in &rounds {
+        assert_eq!(round.fixtures.len(), 3);
+        for fixture in &round.fixtures {
+            let mut pair = [fixture.home.clone(), fixture.away.clone()];
+            pair.sort();
+            assert!(met.insert(pair), "rematch in round {}", round.number);
+        }
+    }
+    let mut byes = rounds
+        .iter()
+        .map(|round| round.bye.clone().unwrap())
+        .collect::<Vec<_>>();
+    byes.sort();
+    byes.dedup();
+    assert_eq!(byes.len(), 5);
+    assert_eq!(rounds, run());
+}
+
+#[test]
+fn swiss_allows_rematches_only_when_unavoidable() {
+    let mut swiss = tournament::swiss::Swiss::new(&["P1", "P2"]).unwrap();
+    let first = swiss.pair_round();
+    swiss
+        .record(&first.fixtures[0].result_line("draw"))
+        .unwrap();
+    let second = swiss.pair_round();
+
+    assert_eq!(pairs(&first), [("P1", "P2")]);
+    assert_eq!(pairs(&second), [("P2", "P1")]);
+}
+
+#[test]
+fn swiss_pairs_quickly_when_a_player_has_met_everyone() {
+    let names = (1..=20).map(|idx| format!("P{idx}")).collect::<Vec<_>>();
+    let players = names.iter().map(String::as_str).collect::<Vec<_>>();
+    let mut swiss = tournament::swiss::Swiss::new(&players).unwrap();
+    for player in &players[..19] {
+        swiss.record(&format!("P20;{player};draw")).unwrap();
+    }
+    let round = swiss.pair_round();
+
+    assert_eq!(round.fixtures.len(), 10);
+    assert_eq!(
+        pairs(&round)
+            .iter()
+            .filter(|&&(home, away)| home == "P20" || away == "P20")
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn swiss_abandoned_round_can_be_paired_again() {
+    let mut swiss = tournament::swiss::Swiss::new(&["P1", "P2", "P3"]).unwrap();
+    assert!(!swiss.abandon_round());
+    let first = swiss.pair_round();
+    assert_eq!(swiss.score("P3"), 3);
+
+    assert!(swiss.abandon_round());
+    assert_eq!(swiss.score("P3"), 0);
+    assert_eq!(swiss.standings().team("P3"), None);
+    assert_eq!(swiss.pair_round(), first);
+}